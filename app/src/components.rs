@@ -1,6 +1,14 @@
 pub mod member_list;
 pub mod prepare_members;
+pub mod import_members;
+pub mod reaction_editor;
 pub mod parking_lot;
+pub mod markdown;
 pub mod member_card;
 pub mod header;
 pub mod typography;
+pub mod speaker_timer;
+pub mod history_panel;
+pub mod export_menu;
+pub mod poker;
+pub mod async_standup;
@@ -0,0 +1,211 @@
+use stylist::style;
+use wasm_bindgen::*;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use crate::ctx::meeting::{MeetingActions, MeetingContext};
+use crate::ctx::styles::StyleContext;
+use crate::data::meeting::{AsyncSummary, AsyncUpdate};
+use crate::components::typography::{Typography, TextSize};
+
+const DAY_MS: f64 = 24.0 * 60.0 * 60.0 * 1000.0;
+
+fn local_time(millis: u64) -> String {
+    let date = js_sys::Date::new(&JsValue::from_f64(millis as f64));
+    String::from(date.to_locale_string("default", &JsValue::UNDEFINED))
+}
+
+/// `YYYY-MM-DDTHH:MM` of unix milliseconds in local time, the format of datetime inputs.
+fn datetime_input_value(millis: f64) -> String {
+    let date = js_sys::Date::new(&JsValue::from_f64(millis));
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}",
+        date.get_full_year(),
+        date.get_month() + 1,
+        date.get_date(),
+        date.get_hours(),
+        date.get_minutes(),
+    )
+}
+
+/// Unix milliseconds of a datetime input value, read as local time.
+fn parse_datetime_input(value: &str) -> Option<u64> {
+    let millis = js_sys::Date::new(&JsValue::from_str(value)).get_time();
+    match millis.is_nan() {
+        true => None,
+        false => Some(millis as u64),
+    }
+}
+
+fn update_html(update: &AsyncUpdate) -> Html {
+    let title = match &update.reaction {
+        Some(symbol) => format!("{} {}", update.name, symbol),
+        None => update.name.clone(),
+    };
+    let notes = [("Yesterday", &update.notes.yesterday), ("Today", &update.notes.today), ("Blockers", &update.notes.blockers)]
+        .into_iter()
+        .filter(|(_, note)| !note.is_empty())
+        .map(|(label, note)| html! {
+            <Typography size={TextSize::Body}>{ format!("{}: {}", label, note) }</Typography>
+        })
+        .collect::<Html>();
+    html! {
+        <div class="update">
+            <Typography size={TextSize::Body}><strong>{ title }</strong></Typography>
+            {
+                match update.submitted_at {
+                    Some(_) => notes,
+                    None => html! { <Typography size={TextSize::Body}>{ "No update" }</Typography> },
+                }
+            }
+        </div>
+    }
+}
+
+fn summary_html(summary: &AsyncSummary) -> Html {
+    let missing = summary.updates.iter().filter(|u| u.submitted_at.is_none()).count();
+    html! {
+        <div class="summary">
+            <Typography size={TextSize::H4}>{ &summary.date }</Typography>
+            <Typography size={TextSize::Body}>
+                { format!("Closed {}, {} without an update", local_time(summary.closed_at), missing) }
+            </Typography>
+            { summary.updates.iter().map(update_html).collect::<Html>() }
+        </div>
+    }
+}
+
+/// Async standup: members submit their notes and reaction from their card until the deadline,
+/// then the standup closes into a dated summary.
+#[function_component(AsyncStandupPanel)]
+pub fn async_standup_panel() -> Html {
+    let meeting_ctx = use_context::<MeetingContext>().expect("no ctx found");
+    let style_ctx = use_context::<StyleContext>().expect("no ctx found");
+    let deadline = use_state(|| datetime_input_value(js_sys::Date::now() + DAY_MS));
+    let summaries = use_state(Vec::<AsyncSummary>::new);
+    {
+        let ctx = meeting_ctx.clone();
+        let summaries = summaries.clone();
+        use_effect_with_deps(
+            move |_| {
+                let cb = Callback::from(move |result| {
+                    match result {
+                        Ok(list) => summaries.set(list),
+                        Err(msg) => log::error!("{:?}", msg),
+                    }
+                });
+                ctx.fetch_async_summaries(cb);
+                || ()
+            },
+            meeting_ctx.state.async_standup.is_some(),
+        );
+    }
+
+    let change_deadline = {
+        let deadline = deadline.clone();
+        Callback::from(move |e: InputEvent| {
+            let target = e.target().expect("Event should have a target when dispatched");
+            deadline.set(target.unchecked_into::<HtmlInputElement>().value());
+        })
+    };
+    let deadline_millis = parse_datetime_input(&deadline);
+    let start = {
+        let ctx = meeting_ctx.clone();
+        Callback::from(move |_| {
+            if let Some(deadline) = deadline_millis {
+                ctx.dispatch(MeetingActions::StartAsyncStandup(deadline));
+            }
+        })
+    };
+    let close = {
+        let ctx = meeting_ctx.clone();
+        Callback::from(move |_| ctx.dispatch(MeetingActions::CloseAsyncStandup))
+    };
+
+    let container = use_state(|| {
+        style!(r#"
+            display: flex;
+            flex-direction: column;
+            gap: 8px 0;
+            margin: 0 32px 16px 0;
+            padding: 16px;
+            border-radius: 16px;
+            background: #2C344A;
+            input {
+                padding: 4px 8px;
+                border: 1px solid #aaa;
+                border-radius: 4px;
+                background-color: #1D3249;
+            }
+            .row {
+                display: flex;
+                flex-wrap: wrap;
+                align-items: center;
+                gap: 8px 16px;
+            }
+            .summaries {
+                max-height: 40vh;
+                overflow-y: auto;
+            }
+            .summary {
+                margin-top: 12px;
+            }
+            .update {
+                margin: 4px 0 0 8px;
+            }
+        "#).expect("Failed to create style").get_class_name().to_string()
+    });
+
+    let members = &meeting_ctx.state.members;
+    let status = match &meeting_ctx.state.async_standup {
+        None => html! {
+            <div class="row">
+                <Typography size={TextSize::Body}>{ "Collect updates until" }</Typography>
+                <input type="datetime-local" value={deadline.to_string()} oninput={change_deadline} />
+                <button
+                    class={style_ctx.outline_btn.to_string()}
+                    disabled={deadline_millis.is_none()}
+                    onclick={start}
+                >
+                    { "Start async standup" }
+                </button>
+            </div>
+        },
+        Some(standup) => {
+            let pending = members
+                .iter()
+                .filter(|m| !standup.has_submitted(&m.id))
+                .map(|m| m.name.clone())
+                .collect::<Vec<String>>();
+            html! {
+                <>
+                    <Typography size={TextSize::Body}>
+                        { format!("Updates are due {}. Members submit them by saving the notes on their card.", local_time(standup.deadline)) }
+                    </Typography>
+                    <div class="row">
+                        <Typography size={TextSize::Body}>
+                            { format!("{} of {} submitted", members.len() - pending.len(), members.len()) }
+                        </Typography>
+                        <button class={style_ctx.outline_btn.to_string()} onclick={close}>{ "Close now" }</button>
+                    </div>
+                    {
+                        match pending.is_empty() {
+                            true => html! {},
+                            false => html! {
+                                <Typography size={TextSize::Body}>{ format!("Waiting for {}", pending.join(", ")) }</Typography>
+                            },
+                        }
+                    }
+                </>
+            }
+        },
+    };
+
+    html! {
+        <div class={container.to_string()}>
+            { status }
+            <div class="summaries">
+                { summaries.iter().map(summary_html).collect::<Html>() }
+            </div>
+        </div>
+    }
+}
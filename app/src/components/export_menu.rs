@@ -0,0 +1,76 @@
+use stylist::style;
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use yew::prelude::*;
+use crate::ctx::{meeting::MeetingContext, styles::StyleContext};
+use crate::data::meeting::ExportFormat;
+use crate::components::typography::{Typography, TextSize};
+
+#[function_component(ExportMenu)]
+pub fn export_menu() -> Html {
+    let meeting_ctx = use_context::<MeetingContext>().expect("no ctx found");
+    let style_ctx = use_context::<StyleContext>().expect("no ctx found");
+    let copied = use_state(|| false);
+
+    let copy_markdown = {
+        let ctx = meeting_ctx.clone();
+        let copied = copied.clone();
+        Callback::from(move |_| {
+            let copied = copied.clone();
+            let cb = Callback::from(move |result| {
+                match result {
+                    Ok(markdown) => {
+                        let copied = copied.clone();
+                        spawn_local(async move {
+                            let nav = web_sys::window().unwrap().navigator();
+                            let clip = nav.clipboard().unwrap();
+                            JsFuture::from(clip.write_text(&markdown)).await.unwrap();
+                            copied.set(true);
+                        })
+                    },
+                    Err(msg) => log::error!("{:?}", msg),
+                }
+            });
+            ctx.fetch_export(ExportFormat::Markdown, cb);
+        })
+    };
+
+    let container = use_state(|| {
+        style!(r#"
+            position: absolute;
+            right: 0;
+            top: 48px;
+            z-index: 10;
+            display: flex;
+            flex-direction: column;
+            gap: 8px 0;
+            width: 200px;
+            padding: 16px;
+            border-radius: 16px;
+            background: #2C344A;
+            box-shadow: 8px 8px 16px #171b28;
+            a {
+                color: inherit;
+            }
+        "#).expect("Failed to create style").get_class_name().to_string()
+    });
+
+    let downloads = ExportFormat::itr().map(|format| {
+        match meeting_ctx.export_url(*format) {
+            Some(url) => html! {
+                <a href={url} download="">
+                    <Typography size={TextSize::Body}>{ format!("Download {}", format.label()) }</Typography>
+                </a>
+            },
+            None => html! {},
+        }
+    }).collect::<Html>();
+
+    html! {
+        <div class={container.to_string()}>
+            <button class={style_ctx.outline_btn.clone()} onclick={copy_markdown}>
+                { if *copied { "Copied" } else { "Copy Markdown" } }
+            </button>
+            { downloads }
+        </div>
+    }
+}
@@ -1,7 +1,10 @@
+use stylist::style;
 use wasm_bindgen_futures::{spawn_local, JsFuture};
 use yew::prelude::*;
 
 use crate::ctx::{meeting::MeetingContext, styles::StyleContext};
+use crate::components::history_panel::HistoryPanel;
+use crate::components::export_menu::ExportMenu;
 
 #[function_component(Header)]
 pub fn members_list() -> Html {
@@ -25,6 +28,28 @@ pub fn members_list() -> Html {
         })
     };
 
+    let show_history = use_state(|| false);
+    let toggle_history = {
+        let show_history = show_history.clone();
+        Callback::from(move |_| {
+            show_history.set(!*show_history)
+        })
+    };
+    let show_export = use_state(|| false);
+    let toggle_export = {
+        let show_export = show_export.clone();
+        Callback::from(move |_| {
+            show_export.set(!*show_export)
+        })
+    };
+    let actions = use_state(|| {
+        style!(r#"
+            position: relative;
+            display: flex;
+            gap: 0 16px;
+        "#).expect("Failed to create style").get_class_name().to_string()
+    });
+
     html! {
         <nav class={style_ctx.header.to_string()}>
             <div>
@@ -32,13 +57,37 @@ pub fn members_list() -> Html {
                     {"Standup board"}
                 </p>
             </div>
-            <div>
+            <div class={actions.to_string()}>
+                <button
+                    class={style_ctx.outline_btn.clone()}
+                    onclick={toggle_history}
+                >
+                    {"History"}
+                </button>
+                <button
+                    class={style_ctx.outline_btn.clone()}
+                    onclick={toggle_export}
+                >
+                    {"Export"}
+                </button>
                 <button
                     class={style_ctx.outline_btn.clone()}
                     onclick={copy}
                 >
                     {"Share"}
                 </button>
+                {
+                    match *show_history {
+                        true => html! { <HistoryPanel /> },
+                        false => html! {},
+                    }
+                }
+                {
+                    match *show_export {
+                        true => html! { <ExportMenu /> },
+                        false => html! {},
+                    }
+                }
             </div>
         </nav>
     }
@@ -0,0 +1,108 @@
+use stylist::style;
+use wasm_bindgen::JsValue;
+use yew::prelude::*;
+use crate::ctx::meeting::MeetingContext;
+use crate::data::meeting::{HistoryEntry, HistoryKind, HistoryMember};
+use crate::components::typography::{Typography, TextSize};
+
+const HISTORY_DAYS: u64 = 30;
+const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+fn local_date(recorded_at: u64) -> String {
+    let date = js_sys::Date::new(&JsValue::from_f64(recorded_at as f64));
+    String::from(date.to_locale_date_string("default", &JsValue::UNDEFINED))
+}
+
+fn member_name(member: &HistoryMember) -> String {
+    member.name.clone().unwrap_or_else(|| String::from("(removed)"))
+}
+
+/// Entries grouped by local date, newest day first.
+fn group_by_day(entries: &[HistoryEntry]) -> Vec<(String, Vec<&HistoryEntry>)> {
+    let mut days: Vec<(String, Vec<&HistoryEntry>)> = Vec::new();
+    for entry in entries.iter().rev() {
+        let date = local_date(entry.recorded_at);
+        match days.last_mut() {
+            Some((day, day_entries)) if *day == date => day_entries.push(entry),
+            _ => days.push((date, vec![entry])),
+        }
+    }
+    days
+}
+
+#[function_component(HistoryPanel)]
+pub fn history_panel() -> Html {
+    let meeting_ctx = use_context::<MeetingContext>().expect("no ctx found");
+    let entries = use_state(Vec::<HistoryEntry>::new);
+    {
+        let ctx = meeting_ctx.clone();
+        let entries = entries.clone();
+        use_effect_with_deps(
+            move |_| {
+                let from = (js_sys::Date::now() as u64).saturating_sub(HISTORY_DAYS * DAY_MS);
+                let cb = Callback::from(move |result| {
+                    match result {
+                        Ok(history) => entries.set(history),
+                        Err(msg) => log::error!("{:?}", msg),
+                    }
+                });
+                ctx.fetch_history(Some(from), None, cb);
+                || ()
+            },
+            meeting_ctx.state.version,
+        );
+    }
+
+    let container = use_state(|| {
+        style!(r#"
+            position: absolute;
+            right: 0;
+            top: 48px;
+            z-index: 10;
+            width: 320px;
+            max-height: 60vh;
+            overflow-y: auto;
+            padding: 16px;
+            border-radius: 16px;
+            background: #2C344A;
+            box-shadow: 8px 8px 16px #171b28;
+            .day {
+                margin-bottom: 12px;
+            }
+            .entry {
+                margin: 4px 0 0 8px;
+            }
+        "#).expect("Failed to create style").get_class_name().to_string()
+    });
+
+    let days = group_by_day(&entries);
+    let content = match days.is_empty() {
+        true => html! { <Typography size={TextSize::Body}>{"Nothing recorded yet"}</Typography> },
+        false => days.iter().map(|(day, day_entries)| {
+            let items = day_entries.iter().map(|entry| {
+                let names = entry.members.iter().map(member_name).collect::<Vec<String>>();
+                let text = match entry.kind {
+                    HistoryKind::Leader => format!("Leader: {}", names.join(", ")),
+                    HistoryKind::Order => format!("Order: {}", names.join(" \u{2192} ")),
+                };
+                html! {
+                    <div class="entry">
+                        <Typography size={TextSize::Body}>{ text }</Typography>
+                    </div>
+                }
+            }).collect::<Html>();
+            html! {
+                <div class="day">
+                    <Typography size={TextSize::H4}>{ day }</Typography>
+                    { items }
+                </div>
+            }
+        }).collect::<Html>(),
+    };
+
+    html! {
+        <div class={container.to_string()}>
+            { content }
+        </div>
+    }
+}
@@ -0,0 +1,114 @@
+use stylist::style;
+use wasm_bindgen::*;
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::{HtmlInputElement, HtmlTextAreaElement};
+use yew::prelude::*;
+use crate::ctx::meeting::{MeetingActions, MeetingContext};
+use crate::ctx::styles::StyleContext;
+use crate::data::meeting::Member;
+use crate::components::typography::{Typography, TextSize};
+
+/// Names in pasted text or a CSV file, one per line or separated by commas.
+/// Blank names and names already on the board are left out, compared case-insensitively.
+fn parse_names(text: &str, members: &[Member]) -> Vec<String> {
+    let mut seen: Vec<String> = members.iter().map(|m| m.name.trim().to_lowercase()).collect();
+    let mut names = Vec::new();
+    for name in text.split(['\n', '\r', ',', ';', '\t']) {
+        let name = name.trim().trim_matches('"').trim();
+        if name.is_empty() || seen.contains(&name.to_lowercase()) {
+            continue;
+        }
+        seen.push(name.to_lowercase());
+        names.push(name.to_string());
+    }
+    names
+}
+
+#[function_component(ImportMembers)]
+pub fn import_members() -> Html {
+    let meeting_ctx = use_context::<MeetingContext>().expect("no ctx found");
+    let style_ctx = use_context::<StyleContext>().expect("no ctx found");
+    let text = use_state(|| String::from(""));
+    let names = parse_names(&text, &meeting_ctx.state.members);
+
+    let change_text = {
+        let text = text.clone();
+        Callback::from(move |e: InputEvent| {
+            let target = e.target().expect("Event should have a target when dispatched");
+            text.set(target.unchecked_into::<HtmlTextAreaElement>().value());
+        })
+    };
+    let upload = {
+        let text = text.clone();
+        Callback::from(move |e: Event| {
+            let target = e.target().expect("Event should have a target when dispatched");
+            let file = target.unchecked_into::<HtmlInputElement>().files().and_then(|files| files.get(0));
+            if let Some(file) = file {
+                let text = text.clone();
+                spawn_local(async move {
+                    match JsFuture::from(file.text()).await {
+                        Ok(content) => text.set(content.as_string().unwrap_or_default()),
+                        Err(er) => log::error!("{:?}", er),
+                    }
+                });
+            }
+        })
+    };
+    let add_members = {
+        let ctx = meeting_ctx.clone();
+        let text = text.clone();
+        let names = names.clone();
+        Callback::from(move |_| {
+            if !names.is_empty() {
+                ctx.dispatch(MeetingActions::AddMembers(names.clone()));
+                text.set(String::from(""));
+            }
+        })
+    };
+
+    let container = use_state(|| {
+        style!(r#"
+            display: flex;
+            flex-direction: column;
+            gap: 8px 0;
+            margin: 0 32px 16px 0;
+            padding: 16px;
+            border-radius: 16px;
+            background: #2C344A;
+            textarea {
+                min-height: 96px;
+                resize: vertical;
+                padding: 8px;
+                border: 1px solid #aaa;
+                border-radius: 4px;
+                background-color: #1D3249;
+            }
+            .actions {
+                display: flex;
+                align-items: center;
+                gap: 0 16px;
+            }
+        "#).expect("Failed to create style").get_class_name().to_string()
+    });
+
+    html! {
+        <div class={container.to_string()}>
+            <textarea
+                placeholder="One name per line, or separated by commas"
+                value={text.to_string()}
+                oninput={change_text}
+            ></textarea>
+            <div class="actions">
+                <input type="file" accept=".csv,.txt,text/csv,text/plain" onchange={upload} />
+                <Typography size={TextSize::Body}>{ format!("{} new", names.len()) }</Typography>
+                <button
+                    class={style_ctx.outline_btn.to_string()}
+                    disabled={names.is_empty()}
+                    onclick={add_members}
+                >
+                    { "Add all" }
+                </button>
+            </div>
+        </div>
+    }
+}
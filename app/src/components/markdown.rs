@@ -0,0 +1,109 @@
+use std::ops::Range;
+use pulldown_cmark::{Event, Options, Parser, Tag};
+use yew::prelude::*;
+
+/// Schemes links may use. Links with any other scheme (`javascript:`, `data:`...) lose their target.
+const LINK_SCHEMES: [&str; 3] = ["http:", "https:", "mailto:"];
+
+fn safe_url(url: &str) -> Option<String> {
+    let url = url.trim();
+    let lower = url.to_lowercase();
+    let scheme = lower
+        .find(|c: char| matches!(c, ':' | '/' | '?' | '#'))
+        .map(|i| &lower[..=i])
+        .filter(|prefix| prefix.ends_with(':'));
+    match scheme {
+        Some(scheme) if !LINK_SCHEMES.contains(&scheme) => None,
+        _ => Some(url.to_string()),
+    }
+}
+
+/// `text` with the task marker found at `range` flipped.
+fn toggle_task(text: &str, range: Range<usize>, checked: bool) -> String {
+    let marker = if checked { "[ ]" } else { "[x]" };
+    format!("{}{}{}", &text[..range.start], marker, &text[range.end..])
+}
+
+fn element(tag: Tag, children: Html) -> Html {
+    match tag {
+        Tag::Paragraph => html! { <p>{ children }</p> },
+        Tag::Heading(level, _, _) => {
+            let name = format!("h{}", level as usize);
+            html! { <@{name}>{ children }</@> }
+        },
+        Tag::BlockQuote => html! { <blockquote>{ children }</blockquote> },
+        Tag::CodeBlock(_) => html! { <pre><code>{ children }</code></pre> },
+        Tag::List(Some(start)) => html! { <ol start={start.to_string()}>{ children }</ol> },
+        Tag::List(None) => html! { <ul>{ children }</ul> },
+        Tag::Item => html! { <li>{ children }</li> },
+        Tag::Table(_) => html! { <table>{ children }</table> },
+        Tag::TableHead => html! { <thead><tr>{ children }</tr></thead> },
+        Tag::TableRow => html! { <tr>{ children }</tr> },
+        Tag::TableCell => html! { <td>{ children }</td> },
+        Tag::Emphasis => html! { <em>{ children }</em> },
+        Tag::Strong => html! { <strong>{ children }</strong> },
+        Tag::Strikethrough => html! { <del>{ children }</del> },
+        Tag::Link(_, url, title) => match safe_url(&url) {
+            Some(href) => html! {
+                <a href={href} title={title.to_string()} target="_blank" rel="noopener noreferrer">{ children }</a>
+            },
+            None => children,
+        },
+        // Images are not loaded, only their description is shown.
+        Tag::Image(_, _, _) | Tag::FootnoteDefinition(_) => children,
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct MarkdownProps {
+    pub text: String,
+    /// Called with the whole text once a task checkbox was clicked.
+    pub on_change: Callback<String>,
+}
+
+/// Renders `text` as Markdown. Raw HTML is shown as typed, never interpreted.
+#[function_component(Markdown)]
+pub fn markdown(MarkdownProps { text, on_change }: &MarkdownProps) -> Html {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TASKLISTS);
+
+    // Children of the elements being built, innermost last.
+    let mut stack: Vec<Vec<Html>> = vec![Vec::new()];
+    for (event, range) in Parser::new_ext(text, options).into_offset_iter() {
+        let node = match event {
+            Event::Start(_) => {
+                stack.push(Vec::new());
+                continue;
+            },
+            Event::End(tag) => {
+                let children = stack.pop().unwrap_or_default().into_iter().collect::<Html>();
+                element(tag, children)
+            },
+            Event::Text(text) | Event::Html(text) => html! { { text.to_string() } },
+            Event::Code(code) => html! { <code>{ code.to_string() }</code> },
+            Event::FootnoteReference(name) => html! { { format!("[^{}]", name) } },
+            Event::SoftBreak => html! { { "\n" } },
+            Event::HardBreak => html! { <br /> },
+            Event::Rule => html! { <hr /> },
+            Event::TaskListMarker(checked) => {
+                let onclick = {
+                    let text = text.clone();
+                    let on_change = on_change.clone();
+                    Callback::from(move |_| on_change.emit(toggle_task(&text, range.clone(), checked)))
+                };
+                html! { <input type="checkbox" checked={checked} onclick={onclick} /> }
+            },
+        };
+        if let Some(children) = stack.last_mut() {
+            children.push(node);
+        }
+    }
+
+    html! {
+        <>
+            { stack.into_iter().flatten().collect::<Html>() }
+        </>
+    }
+}
@@ -1,8 +1,12 @@
 use stylist::style;
+use wasm_bindgen::*;
+use web_sys::HtmlTextAreaElement;
 use yew::prelude::*;
-use crate::data::meeting::{ReactionType, Member};
+use crate::data::meeting::{Attendance, MemberNotes, MemberPin, Member, Reaction, SpeakerTimer, NO_REACTION};
+use crate::data::reaction::{reaction_symbol, reaction_time, NO_REACTION_SYMBOL};
 use crate::{data, ctx::styles::StyleContext};
 use crate::components::typography::{Typography, TextSize};
+use crate::components::speaker_timer::Countdown;
 
 #[derive(PartialEq)]
 pub enum Flip {
@@ -15,34 +19,72 @@ pub struct FrontProps {
     pub member: data::meeting::Member,
     pub on_remove: Callback<data::meeting::Member>,
     pub on_flip: Callback<Flip>,
+    pub on_select_speaker: Callback<data::meeting::Member>,
+    pub on_set_attendance: Callback<data::meeting::Member>,
+    pub on_pin: Callback<data::meeting::Member>,
     pub is_leader: bool,
-    pub order: usize
+    pub order: usize,
+    /// Timer of the meeting, set only while this member is speaking.
+    pub speaking_timer: Option<SpeakerTimer>,
+    pub clock_offset_ms: i64,
+    /// The member has a hidden vote in the running poker round.
+    pub voted: bool,
+    /// Whether the member has submitted to the open async standup, `None` without one.
+    pub submitted: Option<bool>,
+    pub reactions: Vec<Reaction>,
 }
 
-fn emoji(reaction_type: &ReactionType) -> &'static str {
-    match reaction_type {
-        ReactionType::THUMBSUP => "\u{1F44D}",
-        ReactionType::THUMBSDOWN => "\u{1F44E}",
-        ReactionType::CLAP => "\u{1F44F}",
-        ReactionType::SMILE => "\u{1F642}",
-        ReactionType::ZERO => "\u{0030}\u{fe0f}\u{20e3}",
-        ReactionType::I => "\u{0031}\u{fe0f}\u{20e3}",
-        ReactionType::II => "\u{0032}\u{fe0f}\u{20e3}",
-        ReactionType::III => "\u{0033}\u{fe0f}\u{20e3}",
-        ReactionType::IV => "\u{0034}\u{fe0f}\u{20e3}",
-        ReactionType::V => "\u{0035}\u{fe0f}\u{20e3}",
-        ReactionType::VI => "\u{0036}\u{fe0f}\u{20e3}",
-        ReactionType::VII => "\u{0037}\u{fe0f}\u{20e3}",
-        ReactionType::VIII => "\u{0038}\u{fe0f}\u{20e3}",
-        ReactionType::IX => "\u{0039}\u{fe0f}\u{20e3}",
-        ReactionType::X => "\u{1F51F}",
-        ReactionType::NONE => "\u{1F610}"
+fn attendance_icon(attendance: &Attendance) -> &'static str {
+    match attendance {
+        Attendance::Present => "person",
+        Attendance::Absent => "person_off",
+        Attendance::OutOfOffice => "flight",
+    }
+}
+
+fn next_attendance(attendance: &Attendance) -> Attendance {
+    match attendance {
+        Attendance::Present => Attendance::Absent,
+        Attendance::Absent => Attendance::OutOfOffice,
+        Attendance::OutOfOffice => Attendance::Present,
+    }
+}
+
+fn pin_icon(pin: &MemberPin) -> &'static str {
+    match pin {
+        MemberPin::None => "push_pin",
+        MemberPin::First => "vertical_align_top",
+        MemberPin::Last => "vertical_align_bottom",
+    }
+}
+
+/// Titled notes of `notes`, leaving out the empty ones.
+fn note_items(notes: &MemberNotes) -> Vec<(&'static str, &str)> {
+    [("Yesterday", &notes.yesterday), ("Today", &notes.today), ("Blockers", &notes.blockers)]
+        .into_iter()
+        .filter(|(_, note)| !note.trim().is_empty())
+        .map(|(title, note)| (title, note.as_str()))
+        .collect()
+}
+
+fn next_pin(pin: &MemberPin) -> MemberPin {
+    match pin {
+        MemberPin::None => MemberPin::First,
+        MemberPin::First => MemberPin::Last,
+        MemberPin::Last => MemberPin::None,
     }
 }
 
 #[function_component(Front)]
-pub fn front(FrontProps { is_leader, member, on_remove, order, on_flip }: &FrontProps) -> Html {
+pub fn front(FrontProps { is_leader, member, on_remove, order, on_flip, on_select_speaker, on_set_attendance, on_pin, speaking_timer, clock_offset_ms, voted, submitted, reactions }: &FrontProps) -> Html {
     let style_ctx = use_context::<StyleContext>().expect("no ctx found");
+    let select_speaker = {
+        let on_select_speaker = on_select_speaker.clone();
+        let mem = member.clone();
+        Callback::from(move |_| {
+            on_select_speaker.emit(mem.clone())
+        })
+    };
     let on_remove_member = {
         let on_remove = on_remove.clone();
         let mem = member.clone();
@@ -51,6 +93,28 @@ pub fn front(FrontProps { is_leader, member, on_remove, order, on_flip }: &Front
         })
     };
 
+    let toggle_attendance = {
+        let on_set_attendance = on_set_attendance.clone();
+        let mem = member.clone();
+        Callback::from(move |_| {
+            on_set_attendance.emit(Member {
+                attendance: next_attendance(&mem.attendance),
+                ..(mem.clone())
+            })
+        })
+    };
+
+    let toggle_pin = {
+        let on_pin = on_pin.clone();
+        let mem = member.clone();
+        Callback::from(move |_| {
+            on_pin.emit(Member {
+                pin: next_pin(&mem.pin),
+                ..(mem.clone())
+            })
+        })
+    };
+
     let flip_to_back = {
         let on_flip = on_flip.clone();
         Callback::from(move |_| {
@@ -130,15 +194,79 @@ pub fn front(FrontProps { is_leader, member, on_remove, order, on_flip }: &Front
         ).expect("failed to convert css");
         s.get_class_name().to_string()
     });
-    let emoji = emoji(&member.reaction);
+    let card_notes = use_state(|| {
+        let s = style!(
+            r#"
+                padding: 0 4px 4px;
+                display: flex;
+                flex-direction: column;
+                .note {
+                    white-space: nowrap;
+                    text-overflow: ellipsis;
+                    overflow: hidden;
+                }
+            "#
+        ).expect("failed to convert css");
+        s.get_class_name().to_string()
+    });
+    let notes = note_items(&member.notes).into_iter().map(|(title, note)| html! {
+        <div class="note" title={note.to_string()}>
+            <Typography size={TextSize::Body}>{ format!("{}: {}", title, note) }</Typography>
+        </div>
+    }).collect::<Html>();
+    let emoji = reaction_symbol(reactions, &member.reaction);
+    let emoji_title = member.reaction_at.map(|at| format!("Since {}", reaction_time(at)));
     html!{
         <div>
             <div class={&*card_header}>
                 { crown_content }
-                <div>
+                <button class={style_ctx.flat_btn.to_string()} title="Speak now" onclick={select_speaker}>
                     <Typography size={TextSize::H3}>{ order }</Typography>
-                </div>
+                </button>
+                {
+                    match speaking_timer {
+                        Some(timer) => html! {
+                            <Countdown timer={timer.clone()} clock_offset_ms={*clock_offset_ms} />
+                        },
+                        None => html! {},
+                    }
+                }
                 <div>
+                    {
+                        match voted {
+                            true => html! {
+                                <span title="Voted">
+                                    <i class="material-icons">{"how_to_vote"}</i>
+                                </span>
+                            },
+                            false => html! {},
+                        }
+                    }
+                    {
+                        match submitted {
+                            Some(true) => html! {
+                                <span title="Update submitted">
+                                    <i class="material-icons">{"task_alt"}</i>
+                                </span>
+                            },
+                            Some(false) => html! {
+                                <span title="Update pending">
+                                    <i class="material-icons">{"pending"}</i>
+                                </span>
+                            },
+                            None => html! {},
+                        }
+                    }
+                    <button class={style_ctx.icon_btn.to_string()} title="Pin in the order" onclick={toggle_pin}>
+                        <span>
+                            <i class="material-icons">{pin_icon(&member.pin)}</i>
+                        </span>
+                    </button>
+                    <button class={style_ctx.icon_btn.to_string()} title="Attendance" onclick={toggle_attendance}>
+                        <span>
+                            <i class="material-icons">{attendance_icon(&member.attendance)}</i>
+                        </span>
+                    </button>
                     <button class={style_ctx.icon_btn.to_string()} onclick={on_remove_member}>
                         <span>
                             <i class="material-icons">{"clear"}</i>
@@ -147,7 +275,7 @@ pub fn front(FrontProps { is_leader, member, on_remove, order, on_flip }: &Front
                 </div>
             </div>
             <div>
-                <button class={style_ctx.flat_btn.to_string()} onclick={flip_to_back}>
+                <button class={style_ctx.flat_btn.to_string()} title={emoji_title} onclick={flip_to_back}>
                     <Typography size={TextSize::H1}>
                     {emoji}
                     </Typography>
@@ -160,6 +288,9 @@ pub fn front(FrontProps { is_leader, member, on_remove, order, on_flip }: &Front
                     </Typography>
                 </div>
             </div>
+            <div class={&*card_notes}>
+                { notes }
+            </div>
         </div>
     }
 }
@@ -167,12 +298,38 @@ pub fn front(FrontProps { is_leader, member, on_remove, order, on_flip }: &Front
 #[derive(Properties, PartialEq)]
 pub struct BackProps {
     pub on_flip: Callback<Flip>,
-    pub on_select_reaction: Callback<ReactionType>,
+    /// Emits the key of the picked reaction.
+    pub on_select_reaction: Callback<String>,
+    pub reactions: Vec<Reaction>,
+    pub notes: MemberNotes,
+    pub on_save_notes: Callback<MemberNotes>,
+    /// An async standup is open, saving submits the member's update.
+    pub submitting: bool,
 }
 
 #[function_component(Back)]
-pub fn back(BackProps { on_flip, on_select_reaction }: &BackProps) -> Html {
+pub fn back(BackProps { on_flip, on_select_reaction, reactions, notes, on_save_notes, submitting }: &BackProps) -> Html {
     let style_ctx = use_context::<StyleContext>().expect("no ctx found");
+    let draft = {
+        let notes = notes.clone();
+        use_state(move || notes)
+    };
+    let change_note = |set: fn(&mut MemberNotes, String)| {
+        let draft = draft.clone();
+        Callback::from(move |e: InputEvent| {
+            let target = e.target().expect("Event should have a target when dispatched");
+            let mut notes = (*draft).clone();
+            set(&mut notes, target.unchecked_into::<HtmlTextAreaElement>().value());
+            draft.set(notes);
+        })
+    };
+    let save_notes = {
+        let draft = draft.clone();
+        let on_save_notes = on_save_notes.clone();
+        Callback::from(move |_| {
+            on_save_notes.emit((*draft).clone())
+        })
+    };
 
     let flip_to_front = {
         let on_flip = on_flip.clone();
@@ -182,16 +339,23 @@ pub fn back(BackProps { on_flip, on_select_reaction }: &BackProps) -> Html {
     };
 
 
-    let emojis: Vec<Html> = ReactionType::itr().map(|reaction| {
+    let clear_reaction = {
+        let on_select_reaction = on_select_reaction.clone();
+        Callback::from(move |_| {
+            on_select_reaction.emit(String::from(NO_REACTION))
+        })
+    };
+    let emojis: Vec<Html> = reactions.iter().map(|reaction| {
         let select_reaction = {
             let on_select_reaction = on_select_reaction.clone();
+            let key = reaction.key.clone();
             Callback::from(move |_| {
-                on_select_reaction.emit(reaction.clone())
+                on_select_reaction.emit(key.clone())
             })
         };
         html!(
-            <button class={style_ctx.icon_btn.to_string()} onclick={select_reaction}>
-                <Typography size={TextSize::H4}>{emoji(reaction)}</Typography>
+            <button class={style_ctx.icon_btn.to_string()} title={reaction.label.clone()} onclick={select_reaction}>
+                <Typography size={TextSize::H4}>{&reaction.symbol}</Typography>
             </button>
         )
     }).collect();
@@ -222,6 +386,24 @@ pub fn back(BackProps { on_flip, on_select_reaction }: &BackProps) -> Html {
         ).expect("failed to convert css");
         s.get_class_name().to_string()
     });
+    let notes_editor = use_state(|| {
+        let s = style!(
+            r#"
+                padding: 4px;
+                display: flex;
+                flex-direction: column;
+                gap: 4px;
+                textarea {
+                    resize: vertical;
+                    padding: 4px;
+                    border: 1px solid #aaa;
+                    border-radius: 4px;
+                    background-color: #1D3249;
+                }
+            "#
+        ).expect("failed to convert css");
+        s.get_class_name().to_string()
+    });
     html!{
         <div>
             <div class={&*card_header}>
@@ -230,8 +412,38 @@ pub fn back(BackProps { on_flip, on_select_reaction }: &BackProps) -> Html {
                 </button>
             </div>
             <div class={&*content}>
+                <button class={style_ctx.icon_btn.to_string()} title="No reaction" onclick={clear_reaction}>
+                    <Typography size={TextSize::H4}>{NO_REACTION_SYMBOL}</Typography>
+                </button>
                 { emojis }
             </div>
+            <div class={&*notes_editor}>
+                <textarea
+                    rows="2"
+                    placeholder="Yesterday"
+                    value={draft.yesterday.clone()}
+                    oninput={change_note(|notes, text| notes.yesterday = text)}
+                ></textarea>
+                <textarea
+                    rows="2"
+                    placeholder="Today"
+                    value={draft.today.clone()}
+                    oninput={change_note(|notes, text| notes.today = text)}
+                ></textarea>
+                <textarea
+                    rows="2"
+                    placeholder="Blockers"
+                    value={draft.blockers.clone()}
+                    oninput={change_note(|notes, text| notes.blockers = text)}
+                ></textarea>
+                <button
+                    class={style_ctx.outline_btn.to_string()}
+                    disabled={!*submitting && *draft == *notes}
+                    onclick={save_notes}
+                >
+                    { if *submitting { "Submit update" } else { "Save notes" } }
+                </button>
+            </div>
         </div>
     }
 }
@@ -242,12 +454,25 @@ pub struct MemberCardProps {
     pub member: data::meeting::Member,
     pub on_update_member: Callback<data::meeting::Member>,
     pub on_remove: Callback<data::meeting::Member>,
+    pub on_select_speaker: Callback<data::meeting::Member>,
+    pub on_set_attendance: Callback<data::meeting::Member>,
+    pub on_pin: Callback<data::meeting::Member>,
     pub is_leader: bool,
-    pub order: usize
+    pub order: usize,
+    pub speaking_timer: Option<SpeakerTimer>,
+    pub clock_offset_ms: i64,
+    pub reactions: Vec<Reaction>,
+    /// A poker round takes votes, reactions worth points are cast as hidden votes meanwhile.
+    pub voting: bool,
+    pub voted: bool,
+    /// Emits the id of the member and the key of their vote.
+    pub on_vote: Callback<(String, String)>,
+    pub on_set_notes: Callback<data::meeting::Member>,
+    pub submitted: Option<bool>,
 }
 
 #[function_component(MemberCard)]
-pub fn members_card(MemberCardProps { is_leader, member, on_remove, order, on_update_member }: &MemberCardProps) -> Html {
+pub fn members_card(MemberCardProps { is_leader, member, on_remove, order, on_update_member, on_select_speaker, on_set_attendance, on_pin, speaking_timer, clock_offset_ms, reactions, voting, voted, on_vote, on_set_notes, submitted }: &MemberCardProps) -> Html {
     let style_ctx = use_context::<StyleContext>().expect("no ctx found");
     let flip =  use_state(|| Flip::Front);
     let on_flip = {
@@ -258,10 +483,18 @@ pub fn members_card(MemberCardProps { is_leader, member, on_remove, order, on_up
     };
     let on_update_reaction = {
         let update = on_update_member.clone();
+        let on_vote = on_vote.clone();
+        let voting = *voting;
+        let reactions = reactions.clone();
         let member = member.clone();
         let flip = flip.clone();
-        Callback::from(move |reaction| {
+        Callback::from(move |reaction: String| {
             flip.set(Flip::Front);
+            let estimate = reactions.iter().any(|r| r.key == reaction && r.points.is_some());
+            if voting && estimate {
+                on_vote.emit((member.id.clone(), reaction));
+                return;
+            }
             update.emit(
                 Member {
                     reaction,
@@ -270,20 +503,51 @@ pub fn members_card(MemberCardProps { is_leader, member, on_remove, order, on_up
             )
         })
     };
+    let on_save_notes = {
+        let on_set_notes = on_set_notes.clone();
+        let member = member.clone();
+        let flip = flip.clone();
+        Callback::from(move |notes: MemberNotes| {
+            flip.set(Flip::Front);
+            on_set_notes.emit(Member {
+                notes,
+                ..(member.clone())
+            })
+        })
+    };
+    let mut card_class = classes!(style_ctx.member_card.to_string());
+    if speaking_timer.is_some() {
+        card_class.push("speaking");
+    }
+    if !member.is_present() {
+        card_class.push("absent");
+    }
     html!{
-        <div class={style_ctx.member_card.to_string()}>
+        <div class={card_class}>
             {
                 match &*flip {
                     Flip::Front => html!{<Front 
                         is_leader={is_leader.clone()}
                         on_remove={on_remove}
                         on_flip={on_flip}
+                        on_select_speaker={on_select_speaker}
+                        on_set_attendance={on_set_attendance}
+                        on_pin={on_pin}
                         member={member.clone()}
                         order={order.clone()}
+                        speaking_timer={speaking_timer.clone()}
+                        clock_offset_ms={*clock_offset_ms}
+                        voted={*voted}
+                        submitted={*submitted}
+                        reactions={reactions.clone()}
                     />},
                     Flip::Back => html!{<Back
                             on_flip={on_flip}
                             on_select_reaction={on_update_reaction}
+                            reactions={reactions.clone()}
+                            notes={member.notes.clone()}
+                            on_save_notes={on_save_notes}
+                            submitting={submitted.is_some()}
                         />},
                 }
             }
@@ -8,24 +8,94 @@ pub struct MembersListProps {
     pub on_remove: Callback<data::meeting::Member>,
     pub on_update_member: Callback<data::meeting::Member>,
     pub leader_id: Option<String>,
+    pub current_speaker_id: Option<String>,
+    pub on_select_speaker: Callback<data::meeting::Member>,
+    pub on_set_attendance: Callback<data::meeting::Member>,
+    pub on_pin: Callback<data::meeting::Member>,
+    /// Emits the id of a dropped member and the position it was dropped at.
+    pub on_move: Callback<(String, usize)>,
+    pub timer: data::meeting::SpeakerTimer,
+    pub clock_offset_ms: i64,
+    pub reactions: Vec<data::meeting::Reaction>,
+    pub poker: Option<data::meeting::PokerRound>,
+    /// Emits the id of a member and the key of their estimate in the running poker round.
+    pub on_vote: Callback<(String, String)>,
+    pub on_set_notes: Callback<data::meeting::Member>,
+    pub async_standup: Option<data::meeting::AsyncStandup>,
 }
 
 #[function_component(MembersList)]
-pub fn members_list(MembersListProps { leader_id, members, on_remove, on_update_member }: &MembersListProps) -> Html {
+pub fn members_list(MembersListProps { leader_id, members, on_remove, on_update_member, current_speaker_id, on_select_speaker, on_set_attendance, on_pin, on_move, timer, clock_offset_ms, reactions, poker, on_vote, on_set_notes, async_standup }: &MembersListProps) -> Html {
+    // Id of the member being dragged.
+    let dragging = use_state(|| None::<String>);
     members.iter().enumerate().map(|(i, member)| {
         let is_leader = match leader_id {
             Some(id) => *id == member.id,
             None => false,
         };
         let on_remove = on_remove.clone();
+        let voting = poker.as_ref().map_or(false, |p| !p.revealed);
+        let voted = voting && poker.iter().any(|p| p.voter_ids.contains(&member.id));
+        let submitted = async_standup.as_ref().map(|s| s.has_submitted(&member.id));
+        let speaking_timer = match current_speaker_id {
+            Some(id) if *id == member.id => Some(timer.clone()),
+            _ => None,
+        };
+        let drag_start = {
+            let dragging = dragging.clone();
+            let member_id = member.id.clone();
+            Callback::from(move |_: DragEvent| {
+                dragging.set(Some(member_id.clone()))
+            })
+        };
+        let drag_over = Callback::from(|e: DragEvent| {
+            // Allows dropping on this card.
+            e.prevent_default();
+        });
+        let drop = {
+            let dragging = dragging.clone();
+            let on_move = on_move.clone();
+            Callback::from(move |e: DragEvent| {
+                e.prevent_default();
+                if let Some(member_id) = (*dragging).clone() {
+                    on_move.emit((member_id, i));
+                }
+                dragging.set(None)
+            })
+        };
+        let drag_end = {
+            let dragging = dragging.clone();
+            Callback::from(move |_: DragEvent| {
+                dragging.set(None)
+            })
+        };
         html!{
-            <MemberCard
-                member={member.clone()}
-                on_update_member={on_update_member.clone()}
-                order={i+1}
-                is_leader={is_leader}
-                on_remove={on_remove}
-            />
+            <div
+                draggable="true"
+                ondragstart={drag_start}
+                ondragover={drag_over}
+                ondrop={drop}
+                ondragend={drag_end}
+            >
+                <MemberCard
+                    member={member.clone()}
+                    on_update_member={on_update_member.clone()}
+                    order={i+1}
+                    is_leader={is_leader}
+                    on_remove={on_remove}
+                    on_select_speaker={on_select_speaker.clone()}
+                    on_set_attendance={on_set_attendance.clone()}
+                    on_pin={on_pin.clone()}
+                    speaking_timer={speaking_timer}
+                    clock_offset_ms={*clock_offset_ms}
+                    reactions={reactions.clone()}
+                    voting={voting}
+                    voted={voted}
+                    on_vote={on_vote.clone()}
+                    on_set_notes={on_set_notes.clone()}
+                    submitted={submitted}
+                />
+            </div>
         }
     }).collect::<Html>()
 }
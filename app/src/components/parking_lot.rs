@@ -1,8 +1,12 @@
-use stylist::Style;
+use stylist::{style, Style};
 use yew::prelude::*;
 use wasm_bindgen::{*, prelude::Closure};
-use web_sys::{HtmlInputElement, HtmlTextAreaElement};
+use web_sys::{HtmlInputElement, HtmlSelectElement, HtmlTextAreaElement};
 use crate::ctx::meeting::{MeetingContext, MeetingActions};
+use crate::ctx::styles::StyleContext;
+use crate::data::meeting::{Member, ParkingItem};
+use crate::components::markdown::Markdown;
+use crate::data::memo::{char_index, diff, transform_index, utf16_offset};
 
 fn create_textarea_style(height: &str) -> String {
   let str = format!(r#"
@@ -26,25 +30,285 @@ fn create_textarea_style(height: &str) -> String {
   style.get_class_name().to_string()
 }
 
+/// Options for picking a member, the first one standing for nobody.
+fn member_options(members: &[Member], selected: Option<&String>, nobody: &str) -> Html {
+  let options = members.iter().map(|member| {
+    html! {
+      <option selected={selected == Some(&member.id)}>{ &member.name }</option>
+    }
+  }).collect::<Html>();
+  html! {
+    <>
+      <option selected={selected.is_none()}>{ nobody }</option>
+      { options }
+    </>
+  }
+}
+
+/// Member picked in a select built by `member_options`.
+fn selected_member(e: &Event, members: &[Member]) -> Option<String> {
+  let target = e.target().expect("Event should have a target when dispatched");
+  let index = target.unchecked_into::<HtmlSelectElement>().selected_index();
+  match index {
+    i if i > 0 => members.get(i as usize - 1).map(|m| m.id.clone()),
+    _ => None,
+  }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ParkingItemRowProps {
+  pub item: ParkingItem,
+  pub members: Vec<Member>,
+}
+
+#[function_component(ParkingItemRow)]
+pub fn parking_item_row(ParkingItemRowProps { item, members }: &ParkingItemRowProps) -> Html {
+  let meeting_ctx = use_context::<MeetingContext>().expect("no ctx found");
+  let style_ctx = use_context::<StyleContext>().expect("no ctx found");
+
+  let toggle_resolved = {
+    let ctx = meeting_ctx.clone();
+    let item_id = item.id.clone();
+    Callback::from(move |e: Event| {
+      let target = e.target().expect("Event should have a target when dispatched");
+      let resolved = target.unchecked_into::<HtmlInputElement>().checked();
+      ctx.dispatch(MeetingActions::ResolveParkingItem(item_id.clone(), resolved));
+    })
+  };
+  let change_text = {
+    let ctx = meeting_ctx.clone();
+    let item_id = item.id.clone();
+    Callback::from(move |e: Event| {
+      let target = e.target().expect("Event should have a target when dispatched");
+      let text = target.unchecked_into::<HtmlInputElement>().value();
+      ctx.dispatch(MeetingActions::EditParkingItem(item_id.clone(), text));
+    })
+  };
+  let change_owner = {
+    let ctx = meeting_ctx.clone();
+    let item_id = item.id.clone();
+    let members = members.clone();
+    Callback::from(move |e: Event| {
+      ctx.dispatch(MeetingActions::AssignParkingItem(item_id.clone(), selected_member(&e, &members)));
+    })
+  };
+  let delete = {
+    let ctx = meeting_ctx.clone();
+    let item_id = item.id.clone();
+    Callback::from(move |_| {
+      ctx.dispatch(MeetingActions::DeleteParkingItem(item_id.clone()));
+    })
+  };
+  let author = item.author_id
+    .as_ref()
+    .and_then(|id| members.iter().find(|m| m.id == *id))
+    .map(|m| format!("by {}", m.name))
+    .unwrap_or_default();
+
+  html! {
+    <li class={if item.resolved { "resolved" } else { "" }}>
+      <input type="checkbox" title="Resolved" checked={item.resolved} onchange={toggle_resolved} />
+      <input class="text" type="text" value={item.text.clone()} onchange={change_text} />
+      <span class="author">{ author }</span>
+      <select title="Owner" onchange={change_owner}>
+        { member_options(members, item.owner_id.as_ref(), "No owner") }
+      </select>
+      <button class={style_ctx.icon_btn.to_string()} onclick={delete}>
+        <i class="material-icons">{"clear"}</i>
+      </button>
+    </li>
+  }
+}
+
+#[function_component(ParkingItems)]
+pub fn parking_items() -> Html {
+  let meeting_ctx = use_context::<MeetingContext>().expect("no ctx found");
+  let style_ctx = use_context::<StyleContext>().expect("no ctx found");
+  let members = meeting_ctx.state.members.clone();
+  let new_text = use_state(|| String::from(""));
+  let author_id = use_state(|| None::<String>);
+
+  let add_item = {
+    let ctx = meeting_ctx.clone();
+    let new_text = new_text.clone();
+    let author_id = author_id.clone();
+    move || {
+      if new_text.trim().is_empty() {
+        return;
+      }
+      ctx.dispatch(MeetingActions::AddParkingItem(new_text.to_string(), (*author_id).clone()));
+      new_text.set(String::from(""))
+    }
+  };
+  let click_add = {
+    let add_item = add_item.clone();
+    Callback::from(move |_| add_item())
+  };
+  let keydown = Callback::from(move |e: KeyboardEvent| {
+    if e.key_code() == 13 {
+      add_item()
+    }
+  });
+  let change_text = {
+    let new_text = new_text.clone();
+    Callback::from(move |e: InputEvent| {
+      let target = e.target().expect("Event should have a target when dispatched");
+      new_text.set(target.unchecked_into::<HtmlInputElement>().value());
+    })
+  };
+  let change_author = {
+    let author_id = author_id.clone();
+    let members = members.clone();
+    Callback::from(move |e: Event| {
+      author_id.set(selected_member(&e, &members));
+    })
+  };
+
+  let container = use_state(|| {
+    style!(r#"
+      margin-bottom: 16px;
+      .new-item, li {
+        display: flex;
+        align-items: center;
+        gap: 0 8px;
+      }
+      ul {
+        list-style: none;
+        padding: 0;
+      }
+      input.text {
+        flex: 1;
+        background-color: #1D3249;
+        border: 1px solid #aaa;
+        border-radius: 4px;
+      }
+      li.resolved input.text {
+        text-decoration: line-through;
+        opacity: 0.6;
+      }
+      .author {
+        font-size: 12px;
+        opacity: 0.7;
+      }
+    "#).expect("Failed to create style").get_class_name().to_string()
+  });
+
+  let items = meeting_ctx.state.parking_lot.iter().map(|item| {
+    html! {
+      <ParkingItemRow key={item.id.clone()} item={item.clone()} members={members.clone()} />
+    }
+  }).collect::<Html>();
+
+  html! {
+    <div class={container.to_string()}>
+      <div class="new-item">
+        <input
+          class="text"
+          type="text"
+          placeholder="Park a topic"
+          value={new_text.to_string()}
+          onkeydown={keydown}
+          oninput={change_text}
+        />
+        <select title="Raised by" onchange={change_author}>
+          { member_options(&members, (*author_id).as_ref(), "Raised by") }
+        </select>
+        <button class={style_ctx.icon_btn.to_string()} onclick={click_add}>
+          <i class="material-icons">{"add"}</i>
+        </button>
+      </div>
+      <ul>
+        { items }
+      </ul>
+    </div>
+  }
+}
+
 #[function_component(ParkingLot)]
 pub fn members_list() -> Html {
   let meeting_ctx = use_context::<MeetingContext>().expect("no ctx found");
+  let style_ctx = use_context::<StyleContext>().expect("no ctx found");
   let textarea_ref = use_node_ref();
+  let preview = use_state(|| false);
 
   let state = meeting_ctx.state.clone();
   let memo = state.memo.to_string();
   let textarea = use_state(|| {
     create_textarea_style("100%")
   });
+  let preview_style = use_state(|| {
+    style!(r#"
+      font-size: 18px;
+      min-height: 100%;
+      padding: 16px;
+      border-radius: 10px 0px 0px 10px;
+      background: #20283D;
+      overflow-wrap: anywhere;
+      box-shadow: -5px -5px 10px rgba(0,0,0,0.25),
+                  inset 5px 5px 10px rgba(0,0,0,0.25);
+      a {
+        color: #8AB4F8;
+      }
+      pre, code {
+        background: #1D3249;
+        border-radius: 4px;
+      }
+      pre {
+        padding: 8px;
+        overflow-x: auto;
+      }
+      li > input[type=checkbox] {
+        margin-right: 8px;
+      }
+    "#).expect("Failed to create style").get_class_name().to_string()
+  });
 
-  let change_memo = {
+  let toggle_preview = {
+    let preview = preview.clone();
+    Callback::from(move |_| preview.set(!*preview))
+  };
+  let check_task = {
     let ctx = meeting_ctx.clone();
-    Callback::from(move |e: Event| {
+    // Merged with concurrent edits like typing, so a toggle never overwrites them.
+    Callback::from(move |memo: String| {
+      ctx.dispatch(MeetingActions::EditMemo(memo));
+    })
+  };
+
+  let edit_memo = {
+    let ctx = meeting_ctx.clone();
+    Callback::from(move |e: InputEvent| {
       let target = e.target().expect("Event should have a target when dispatched");
-      let val = target.unchecked_into::<HtmlInputElement>().value();
-      ctx.dispatch(MeetingActions::UpdateMemo(val));
+      let val = target.unchecked_into::<HtmlTextAreaElement>().value();
+      ctx.dispatch(MeetingActions::EditMemo(val));
     })
   };
+
+  // The textarea is left uncontrolled so merging someone else's edit does not
+  // throw the caret to the end while typing.
+  {
+    let textarea_ref = textarea_ref.clone();
+    use_effect_with_deps(
+      move |memo: &String| {
+        if let Some(node) = textarea_ref.cast::<HtmlTextAreaElement>() {
+          let current = node.value();
+          if current != *memo {
+            let ops = diff(&current, memo);
+            let caret = |offset: Option<u32>| {
+              let index = char_index(&current, offset.unwrap_or(0) as usize);
+              utf16_offset(memo, transform_index(index, &ops)) as u32
+            };
+            let start = caret(node.selection_start().ok().flatten());
+            let end = caret(node.selection_end().ok().flatten());
+            node.set_value(memo);
+            let _ = node.set_selection_range(start, end);
+          }
+        }
+        || ()
+      },
+      memo,
+    );
+  }
    
   {
     let textarea_ref = textarea_ref.clone();
@@ -76,13 +340,34 @@ pub fn members_list() -> Html {
     );
   }
 
+  // The textarea stays mounted while previewing so its listeners survive.
+  let preview_content = match *preview {
+    true => html! {
+      <div class={preview_style.to_string()}>
+        <Markdown text={state.memo.clone()} on_change={check_task} />
+      </div>
+    },
+    false => html! {},
+  };
+
   html! {
-    <textarea
-      ref={textarea_ref}
-      class={textarea.to_string()}
-      placeholder="Rarking lot"
-      value={memo.to_string()}
-      onchange={change_memo}
-    ></textarea>
+    <>
+      <ParkingItems />
+      <button
+        class={style_ctx.icon_btn.to_string()}
+        title={if *preview { "Edit" } else { "Preview" }}
+        onclick={toggle_preview}
+      >
+        <i class="material-icons">{if *preview { "edit" } else { "visibility" }}</i>
+      </button>
+      { preview_content }
+      <textarea
+        ref={textarea_ref}
+        class={textarea.to_string()}
+        placeholder="Rarking lot"
+        hidden={*preview}
+        oninput={edit_memo}
+      ></textarea>
+    </>
   }
 }
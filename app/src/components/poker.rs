@@ -0,0 +1,160 @@
+use stylist::style;
+use wasm_bindgen::*;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use crate::ctx::meeting::{MeetingActions, MeetingContext};
+use crate::ctx::styles::StyleContext;
+use crate::data::meeting::PokerSummary;
+use crate::data::reaction::reaction_symbol;
+use crate::components::typography::{Typography, TextSize};
+
+fn summary_text(summary: &PokerSummary) -> String {
+    match summary.consensus {
+        true => format!("Consensus on {}", summary.min),
+        false => format!(
+            "{} to {}, mean {:.1}, median {}",
+            summary.min,
+            summary.max,
+            summary.mean,
+            summary.median,
+        ),
+    }
+}
+
+/// Planning poker round: topic, who has voted, and the votes once the facilitator reveals them.
+/// Votes are cast from the back of the member cards.
+#[function_component(PokerPanel)]
+pub fn poker_panel() -> Html {
+    let meeting_ctx = use_context::<MeetingContext>().expect("no ctx found");
+    let style_ctx = use_context::<StyleContext>().expect("no ctx found");
+    let topic = use_state(|| String::from(""));
+    let members = &meeting_ctx.state.members;
+    let name = |id: &str| members
+        .iter()
+        .find(|m| m.id == id)
+        .map(|m| m.name.clone())
+        .unwrap_or_default();
+
+    let change_topic = {
+        let topic = topic.clone();
+        Callback::from(move |e: InputEvent| {
+            let target = e.target().expect("Event should have a target when dispatched");
+            topic.set(target.unchecked_into::<HtmlInputElement>().value());
+        })
+    };
+    let start_round = {
+        let ctx = meeting_ctx.clone();
+        let topic = topic.clone();
+        Callback::from(move |_| {
+            if !topic.trim().is_empty() {
+                ctx.dispatch(MeetingActions::StartPokerRound(topic.trim().to_string()));
+                topic.set(String::from(""));
+            }
+        })
+    };
+    let reveal_votes = {
+        let ctx = meeting_ctx.clone();
+        Callback::from(move |_| ctx.dispatch(MeetingActions::RevealVotes))
+    };
+    let revote = {
+        let ctx = meeting_ctx.clone();
+        Callback::from(move |_| ctx.dispatch(MeetingActions::Revote))
+    };
+    let end_round = {
+        let ctx = meeting_ctx.clone();
+        Callback::from(move |_| ctx.dispatch(MeetingActions::EndPokerRound))
+    };
+
+    let container = use_state(|| {
+        style!(r#"
+            display: flex;
+            flex-direction: column;
+            gap: 8px 0;
+            margin: 0 32px 16px 0;
+            padding: 16px;
+            border-radius: 16px;
+            background: #2C344A;
+            input {
+                padding: 4px 8px;
+                border: 1px solid #aaa;
+                border-radius: 4px;
+                background-color: #1D3249;
+            }
+            .row {
+                display: flex;
+                flex-wrap: wrap;
+                align-items: center;
+                gap: 8px 16px;
+            }
+        "#).expect("Failed to create style").get_class_name().to_string()
+    });
+
+    let contents = match &meeting_ctx.state.poker {
+        None => html! {
+            <div class="row">
+                <input
+                    type="text"
+                    placeholder="What are we estimating?"
+                    value={topic.to_string()}
+                    oninput={change_topic}
+                />
+                <button
+                    class={style_ctx.outline_btn.to_string()}
+                    disabled={topic.trim().is_empty()}
+                    onclick={start_round}
+                >
+                    { "Start round" }
+                </button>
+            </div>
+        },
+        Some(poker) => {
+            let results = match &poker.votes {
+                Some(votes) => votes.iter().map(|vote| html! {
+                    <Typography size={TextSize::Body}>
+                        { format!("{} {}", name(&vote.member_id), reaction_symbol(&meeting_ctx.state.reactions, &vote.reaction)) }
+                    </Typography>
+                }).collect::<Html>(),
+                None => poker.voter_ids.iter().map(|id| html! {
+                    <Typography size={TextSize::Body}>{ format!("{} \u{2713}", name(id)) }</Typography>
+                }).collect::<Html>(),
+            };
+            let summary = match (&poker.summary, poker.revealed) {
+                (Some(summary), _) => summary_text(summary),
+                (None, true) => String::from("Nobody voted"),
+                (None, false) => format!("{} of {} voted", poker.voter_ids.len(), members.len()),
+            };
+            let controls = match meeting_ctx.is_facilitator() {
+                true => html! {
+                    <div class="row">
+                        {
+                            match poker.revealed {
+                                true => html! {
+                                    <button class={style_ctx.outline_btn.to_string()} onclick={revote}>{ "Re-vote" }</button>
+                                },
+                                false => html! {
+                                    <button class={style_ctx.outline_btn.to_string()} onclick={reveal_votes}>{ "Reveal" }</button>
+                                },
+                            }
+                        }
+                        <button class={style_ctx.outline_btn.to_string()} onclick={end_round}>{ "End round" }</button>
+                    </div>
+                },
+                false => html! {},
+            };
+            html! {
+                <>
+                    <Typography size={TextSize::H4}>{ &poker.topic }</Typography>
+                    <div class="row">{ results }</div>
+                    <Typography size={TextSize::Body}>{ summary }</Typography>
+                    { controls }
+                </>
+            }
+        },
+    };
+
+    html! {
+        <div class={container.to_string()}>
+            { contents }
+        </div>
+    }
+}
@@ -1,11 +1,16 @@
 use stylist::{ style };
 use yew::prelude::*;
-use web_sys::{HtmlInputElement};
+use web_sys::{HtmlInputElement, HtmlSelectElement};
 use wasm_bindgen::*;
 use crate::ctx::meeting::{MeetingActions, MeetingContext};
 use crate::ctx::styles::StyleContext;
 use crate::data;
+use crate::components::async_standup::AsyncStandupPanel;
+use crate::components::import_members::ImportMembers;
 use crate::components::member_list::MembersList;
+use crate::components::poker::PokerPanel;
+use crate::components::reaction_editor::ReactionEditor;
+use crate::components::speaker_timer::TimerControls;
 
 #[function_component(PrepareMembers)]
 pub fn prepare_members() -> Html {
@@ -17,6 +22,10 @@ pub fn prepare_members() -> Html {
     let members = state.members.to_vec();
     let new_member_name: UseStateHandle<String>= use_state(|| String::from(""));
     let leader_id = state.leader_id.clone();
+    let show_import = use_state(|| false);
+    let show_poker = use_state(|| false);
+    let show_reactions = use_state(|| false);
+    let show_async = use_state(|| false);
 
 
     let add_member = {
@@ -28,6 +37,41 @@ pub fn prepare_members() -> Html {
         })
     };
 
+    let toggle_import = {
+        let show_import = show_import.clone();
+        Callback::from(move |_| {
+            show_import.set(!*show_import)
+        })
+    };
+
+    let toggle_poker = {
+        let show_poker = show_poker.clone();
+        Callback::from(move |_| {
+            show_poker.set(!*show_poker)
+        })
+    };
+
+    let toggle_async = {
+        let show_async = show_async.clone();
+        Callback::from(move |_| {
+            show_async.set(!*show_async)
+        })
+    };
+
+    let toggle_reactions = {
+        let show_reactions = show_reactions.clone();
+        Callback::from(move |_| {
+            show_reactions.set(!*show_reactions)
+        })
+    };
+
+    let cast_vote = {
+        let ctx = meeting_ctx.clone();
+        Callback::from(move |(member_id, reaction): (String, String)| {
+            ctx.dispatch(MeetingActions::CastVote(member_id, reaction));
+        })
+    };
+
     let remove_member = {
         let ctx = meeting_ctx.clone();
         Callback::from(move |member: data::meeting::Member| {
@@ -42,6 +86,20 @@ pub fn prepare_members() -> Html {
         })
     };
 
+    let select_speaker = {
+        let ctx = meeting_ctx.clone();
+        Callback::from(move |member: data::meeting::Member| {
+            ctx.dispatch(MeetingActions::JumpToSpeaker(member.id.to_string()));
+        })
+    };
+
+    let set_attendance = {
+        let ctx = meeting_ctx.clone();
+        Callback::from(move |member: data::meeting::Member| {
+            ctx.dispatch(MeetingActions::SetAttendance(member.id.to_string(), member.attendance));
+        })
+    };
+
     let shuffle_members = {
         let ctx = meeting_ctx.clone();
         Callback::from(move |_| {
@@ -57,6 +115,64 @@ pub fn prepare_members() -> Html {
         })
     };
 
+    let change_leader_strategy = {
+        let ctx = meeting_ctx.clone();
+        Callback::from(move |e: Event| {
+            let target = e.target().expect("Event should have a target when dispatched");
+            let index = target.unchecked_into::<HtmlSelectElement>().selected_index();
+            if let Some(strategy) = data::meeting::LeaderStrategy::itr().nth(index as usize) {
+                ctx.dispatch(MeetingActions::SetLeaderStrategy(*strategy));
+            }
+        })
+    };
+    let leader_strategies = data::meeting::LeaderStrategy::itr().map(|strategy| {
+        html! {
+            <option selected={*strategy == state.leader_strategy}>{ strategy.label() }</option>
+        }
+    }).collect::<Html>();
+
+    let change_order_strategy = {
+        let ctx = meeting_ctx.clone();
+        Callback::from(move |e: Event| {
+            let target = e.target().expect("Event should have a target when dispatched");
+            let index = target.unchecked_into::<HtmlSelectElement>().selected_index();
+            if let Some(strategy) = data::meeting::OrderStrategy::itr().nth(index as usize) {
+                ctx.dispatch(MeetingActions::OrderMembers(*strategy));
+            }
+        })
+    };
+    let order_strategies = data::meeting::OrderStrategy::itr().map(|strategy| {
+        html! {
+            <option selected={*strategy == state.order_strategy}>{ strategy.label() }</option>
+        }
+    }).collect::<Html>();
+
+    let pin_member = {
+        let ctx = meeting_ctx.clone();
+        Callback::from(move |member: data::meeting::Member| {
+            ctx.dispatch(MeetingActions::PinMember(member.id.to_string(), member.pin));
+        })
+    };
+
+    let set_notes = {
+        let ctx = meeting_ctx.clone();
+        let async_open = state.async_standup.is_some();
+        Callback::from(move |member: data::meeting::Member| {
+            // While an async standup is open, saving notes submits the member's update.
+            match async_open {
+                true => ctx.dispatch(MeetingActions::SubmitAsyncUpdate(member.id.to_string(), member.notes, member.reaction)),
+                false => ctx.dispatch(MeetingActions::SetMemberNotes(member.id.to_string(), member.notes)),
+            }
+        })
+    };
+
+    let move_member = {
+        let ctx = meeting_ctx.clone();
+        Callback::from(move |(member_id, to_index): (String, usize)| {
+            ctx.dispatch(MeetingActions::MoveMember(member_id, to_index));
+        })
+    };
+
     let keydown = {
         let new_member_name = new_member_name.clone();
         let ctx = meeting_ctx.clone();
@@ -163,29 +279,86 @@ pub fn prepare_members() -> Html {
                                 <i class="material-icons">{"add"}</i>
                             </span>
                         </button>
+                        <button class={style_ctx.icon_btn.to_string()} title="Import names" onclick={toggle_import}>
+                            <i class="material-icons">{"playlist_add"}</i>
+                        </button>
                     </div>
                 </div>
                 <div class={button_group.to_string()}>
+                    <select title="Leader rotation" onchange={change_leader_strategy}>
+                        { leader_strategies }
+                    </select>
                     <button
                         onclick={new_leader}
                         class={style_ctx.outline_btn.to_string()}
                     >
                         { "Today's Leader" }
                     </button>
+                    <select title="Speaking order" onchange={change_order_strategy}>
+                        { order_strategies }
+                    </select>
                     <button
                         onclick={shuffle_members}
                         class={style_ctx.outline_btn.to_string()}
                     >
-                        { "Shuffle" }
+                        { "Reorder" }
+                    </button>
+                    <TimerControls />
+                    <button class={style_ctx.icon_btn.to_string()} title="Reactions" onclick={toggle_reactions}>
+                        <i class="material-icons">{"add_reaction"}</i>
+                    </button>
+                    <button class={style_ctx.icon_btn.to_string()} title="Planning poker" onclick={toggle_poker}>
+                        <i class="material-icons">{"style"}</i>
+                    </button>
+                    <button class={style_ctx.icon_btn.to_string()} title="Async standup" onclick={toggle_async}>
+                        <i class="material-icons">{"schedule_send"}</i>
                     </button>
                 </div>
             </div>
+            {
+                match *show_import {
+                    true => html! { <ImportMembers /> },
+                    false => html! {},
+                }
+            }
+            {
+                match *show_reactions {
+                    true => html! { <ReactionEditor /> },
+                    false => html! {},
+                }
+            }
+            {
+                // A running round stays visible to everyone.
+                match *show_poker || state.poker.is_some() {
+                    true => html! { <PokerPanel /> },
+                    false => html! {},
+                }
+            }
+            {
+                // An open async standup stays visible to everyone.
+                match *show_async || state.async_standup.is_some() {
+                    true => html! { <AsyncStandupPanel /> },
+                    false => html! {},
+                }
+            }
             <div class={style_ctx.member_list.to_string()}>
                 <MembersList
                     leader_id={leader_id.clone()}
                     members={members.to_vec()}
                     on_remove={remove_member}
                     on_update_member={update_member}
+                    current_speaker_id={state.current_speaker_id.clone()}
+                    on_select_speaker={select_speaker}
+                    on_set_attendance={set_attendance}
+                    on_pin={pin_member}
+                    on_move={move_member}
+                    timer={state.timer.clone()}
+                    clock_offset_ms={state.clock_offset_ms}
+                    reactions={state.reactions.clone()}
+                    poker={state.poker.clone()}
+                    on_vote={cast_vote}
+                    on_set_notes={set_notes}
+                    async_standup={state.async_standup.clone()}
                 />
             </div>
         </div>
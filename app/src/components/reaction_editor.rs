@@ -0,0 +1,182 @@
+use stylist::style;
+use wasm_bindgen::*;
+use web_sys::{HtmlInputElement, HtmlTextAreaElement};
+use yew::prelude::*;
+use crate::ctx::meeting::{MeetingActions, MeetingContext};
+use crate::ctx::styles::StyleContext;
+use crate::data::meeting::ReactionReset;
+use crate::data::reaction::{browser_time_zone, format_reactions, format_reset_time, parse_reactions, parse_reset_time, ReactionPreset};
+use crate::components::typography::{Typography, TextSize};
+
+#[function_component(ReactionEditor)]
+pub fn reaction_editor() -> Html {
+    let meeting_ctx = use_context::<MeetingContext>().expect("no ctx found");
+    let style_ctx = use_context::<StyleContext>().expect("no ctx found");
+    let text = {
+        let reactions = meeting_ctx.state.reactions.clone();
+        use_state(move || format_reactions(&reactions))
+    };
+    let reactions = parse_reactions(&text, &meeting_ctx.state.reactions);
+    let reset_time = {
+        let reset = meeting_ctx.state.reaction_reset.clone();
+        use_state(move || reset.as_ref().map(format_reset_time).unwrap_or_else(|| String::from("00:00")))
+    };
+    let time_zone = {
+        let reset = meeting_ctx.state.reaction_reset.clone();
+        use_state(move || reset.map(|r| r.time_zone).unwrap_or_else(browser_time_zone))
+    };
+    let schedule = parse_reset_time(&reset_time)
+        .filter(|_| !time_zone.trim().is_empty())
+        .map(|(hour, minute)| ReactionReset {
+            time_zone: time_zone.trim().to_string(),
+            hour,
+            minute,
+        });
+
+    let change_text = {
+        let text = text.clone();
+        Callback::from(move |e: InputEvent| {
+            let target = e.target().expect("Event should have a target when dispatched");
+            text.set(target.unchecked_into::<HtmlTextAreaElement>().value());
+        })
+    };
+    let save = {
+        let ctx = meeting_ctx.clone();
+        let reactions = reactions.clone();
+        Callback::from(move |_| {
+            if !reactions.is_empty() {
+                ctx.dispatch(MeetingActions::SetReactions(reactions.clone()));
+            }
+        })
+    };
+    let change_reset_time = {
+        let reset_time = reset_time.clone();
+        Callback::from(move |e: InputEvent| {
+            let target = e.target().expect("Event should have a target when dispatched");
+            reset_time.set(target.unchecked_into::<HtmlInputElement>().value());
+        })
+    };
+    let change_time_zone = {
+        let time_zone = time_zone.clone();
+        Callback::from(move |e: InputEvent| {
+            let target = e.target().expect("Event should have a target when dispatched");
+            time_zone.set(target.unchecked_into::<HtmlInputElement>().value());
+        })
+    };
+    let save_schedule = {
+        let ctx = meeting_ctx.clone();
+        let schedule = schedule.clone();
+        Callback::from(move |_| {
+            if schedule.is_some() {
+                ctx.dispatch(MeetingActions::SetReactionReset(schedule.clone()));
+            }
+        })
+    };
+    let disable_schedule = {
+        let ctx = meeting_ctx.clone();
+        Callback::from(move |_| ctx.dispatch(MeetingActions::SetReactionReset(None)))
+    };
+    let clear_reactions = {
+        let ctx = meeting_ctx.clone();
+        Callback::from(move |_| ctx.dispatch(MeetingActions::ResetReactions))
+    };
+    let schedule_status = match &meeting_ctx.state.reaction_reset {
+        Some(reset) => format!("Reactions clear daily at {} ({})", format_reset_time(reset), reset.time_zone),
+        None => String::from("Reactions are kept until cleared"),
+    };
+    let presets = ReactionPreset::itr().map(|preset| {
+        let use_preset = {
+            let text = text.clone();
+            Callback::from(move |_| {
+                text.set(preset.text().to_string())
+            })
+        };
+        html! {
+            <button class={style_ctx.outline_btn.to_string()} onclick={use_preset}>
+                { preset.label() }
+            </button>
+        }
+    }).collect::<Html>();
+
+    let container = use_state(|| {
+        style!(r#"
+            display: flex;
+            flex-direction: column;
+            gap: 8px 0;
+            margin: 0 32px 16px 0;
+            padding: 16px;
+            border-radius: 16px;
+            background: #2C344A;
+            input {
+                padding: 4px 8px;
+                border: 1px solid #aaa;
+                border-radius: 4px;
+                background-color: #1D3249;
+            }
+            textarea {
+                min-height: 96px;
+                resize: vertical;
+                padding: 8px;
+                border: 1px solid #aaa;
+                border-radius: 4px;
+                background-color: #1D3249;
+            }
+            .actions {
+                display: flex;
+                flex-wrap: wrap;
+                align-items: center;
+                gap: 8px 16px;
+            }
+        "#).expect("Failed to create style").get_class_name().to_string()
+    });
+
+    html! {
+        <div class={container.to_string()}>
+            <div class="actions">{ presets }</div>
+            <textarea
+                placeholder={"One reaction per line: a symbol, a label and \"= points\" for estimates, e.g. \u{1F912} sick"}
+                value={text.to_string()}
+                oninput={change_text}
+            ></textarea>
+            <div class="actions">
+                <Typography size={TextSize::Body}>{ format!("{} reactions", reactions.len()) }</Typography>
+                <button
+                    class={style_ctx.outline_btn.to_string()}
+                    disabled={reactions.is_empty()}
+                    onclick={save}
+                >
+                    { "Save" }
+                </button>
+            </div>
+            <div class="actions">
+                <Typography size={TextSize::Body}>{ schedule_status }</Typography>
+                <button class={style_ctx.outline_btn.to_string()} onclick={clear_reactions}>
+                    { "Clear reactions" }
+                </button>
+            </div>
+            <div class="actions">
+                <input type="time" value={reset_time.to_string()} oninput={change_reset_time} />
+                <input
+                    type="text"
+                    placeholder="Time zone, e.g. Europe/Berlin"
+                    value={time_zone.to_string()}
+                    oninput={change_time_zone}
+                />
+                <button
+                    class={style_ctx.outline_btn.to_string()}
+                    disabled={schedule.is_none()}
+                    onclick={save_schedule}
+                >
+                    { "Clear daily" }
+                </button>
+                <button
+                    class={style_ctx.outline_btn.to_string()}
+                    disabled={meeting_ctx.state.reaction_reset.is_none()}
+                    onclick={disable_schedule}
+                >
+                    { "Off" }
+                </button>
+            </div>
+        </div>
+    }
+}
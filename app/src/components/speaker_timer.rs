@@ -0,0 +1,137 @@
+use gloo_timers::callback::Interval;
+use stylist::style;
+use yew::prelude::*;
+use web_sys::HtmlInputElement;
+use wasm_bindgen::*;
+use crate::ctx::meeting::{MeetingActions, MeetingContext};
+use crate::ctx::styles::StyleContext;
+use crate::data::meeting::SpeakerTimer;
+use crate::components::typography::{Typography, TextSize};
+
+/// Current time on the server's clock in unix milliseconds.
+fn server_now(clock_offset_ms: i64) -> u64 {
+    (js_sys::Date::now() as i64 + clock_offset_ms) as u64
+}
+
+fn format_remaining(remaining_ms: i64) -> String {
+    let secs = (remaining_ms.abs() + 999) / 1000;
+    let sign = if remaining_ms < 0 { "+" } else { "" };
+    format!("{}{}:{:02}", sign, secs / 60, secs % 60)
+}
+
+#[derive(Properties, PartialEq)]
+pub struct CountdownProps {
+    pub timer: SpeakerTimer,
+    pub clock_offset_ms: i64,
+}
+
+#[function_component(Countdown)]
+pub fn countdown(CountdownProps { timer, clock_offset_ms }: &CountdownProps) -> Html {
+    let now = use_state(|| server_now(*clock_offset_ms));
+    {
+        let now = now.clone();
+        use_effect_with_deps(
+            move |offset| {
+                let offset = *offset;
+                let interval = Interval::new(250, move || now.set(server_now(offset)));
+                move || drop(interval)
+            },
+            *clock_offset_ms,
+        );
+    }
+    let remaining = timer.remaining_at(*now);
+    let text = use_state(|| {
+        style!(r#"
+            color: #03A688;
+            &.overrun {
+                color: #F25C5C;
+            }
+        "#).expect("Failed to create style").get_class_name().to_string()
+    });
+    let class = match remaining < 0 {
+        true => classes!(text.to_string(), "overrun"),
+        false => classes!(text.to_string()),
+    };
+    html! {
+        <span class={class}>
+            <Typography size={TextSize::Body}>{ format_remaining(remaining) }</Typography>
+        </span>
+    }
+}
+
+#[function_component(TimerControls)]
+pub fn timer_controls() -> Html {
+    let meeting_ctx = use_context::<MeetingContext>().expect("no ctx found");
+    let style_ctx = use_context::<StyleContext>().expect("no ctx found");
+    let timer = meeting_ctx.state.timer.clone();
+
+    let change_duration = {
+        let ctx = meeting_ctx.clone();
+        Callback::from(move |e: Event| {
+            let target = e.target().expect("Event should have a target when dispatched");
+            let val = target.unchecked_into::<HtmlInputElement>().value();
+            if let Ok(seconds) = val.parse::<u64>() {
+                ctx.dispatch(MeetingActions::SetSpeakerTime(seconds));
+            }
+        })
+    };
+    let toggle = {
+        let ctx = meeting_ctx.clone();
+        let running = timer.is_running();
+        Callback::from(move |_| {
+            match running {
+                true => ctx.dispatch(MeetingActions::PauseTimer),
+                false => ctx.dispatch(MeetingActions::StartTimer),
+            }
+        })
+    };
+    let previous = {
+        let ctx = meeting_ctx.clone();
+        Callback::from(move |_| {
+            ctx.dispatch(MeetingActions::PreviousSpeaker);
+        })
+    };
+    let next = {
+        let ctx = meeting_ctx.clone();
+        Callback::from(move |_| {
+            ctx.dispatch(MeetingActions::NextSpeaker);
+        })
+    };
+
+    let container = use_state(|| {
+        style!(r#"
+            display: flex;
+            align-items: center;
+            gap: 0 8px;
+            input {
+                width: 56px;
+                background-color: #1D3249;
+                border: 1px solid #aaa;
+                border-radius: 4px;
+            }
+        "#).expect("Failed to create style").get_class_name().to_string()
+    });
+
+    html! {
+        <div class={container.to_string()}>
+            <input
+                type="number"
+                min="1"
+                title="Seconds per person"
+                value={timer.duration_secs.to_string()}
+                onchange={change_duration}
+            />
+            <button class={style_ctx.icon_btn.to_string()} onclick={previous}>
+                <i class="material-icons">{"skip_previous"}</i>
+            </button>
+            <button class={style_ctx.icon_btn.to_string()} onclick={toggle}>
+                <i class="material-icons">
+                    { if timer.is_running() { "pause" } else { "play_arrow" } }
+                </i>
+            </button>
+            <button class={style_ctx.icon_btn.to_string()} onclick={next}>
+                <i class="material-icons">{"skip_next"}</i>
+            </button>
+        </div>
+    }
+}
@@ -1,25 +1,50 @@
-use std::{rc::Rc};
+use std::{cell::RefCell, rc::Rc};
 
 use wasm_bindgen_futures::spawn_local;
 use yew::prelude::*;
-use crate::{data::meeting:: { Member, ErrorMsg }, repository::{storage::{get_meeting_id, set_meeting_id}, api::{MeetingResult, API}}};
+use crate::{data::memo::{MemoClient, Operation}, data::meeting:: { AsyncStandup, AsyncSummary, Attendance, ExportFormat, HistoryEntry, LeaderStrategy, Meeting, Member, MemberNotes, MemberPin, OrderStrategy, ParkingItem, PokerRound, Reaction, ReactionReset, ErrorMsg, ErrorCode, SpeakerTimer }, repository::{storage::{get_meeting_id, set_meeting_id, get_facilitator_token, get_poker_round_id, set_poker_round_id}, api::{ApiResult, MeetingResult, API}}};
 pub enum MeetingActions {
     StartMeeting(Option<String>),
+    CreateMeeting,
     UpdateMember(Member),
-    UpdateMemo(String),
+    EditMemo(String),
+    AddParkingItem(String, Option<String>),
+    EditParkingItem(String, String),
+    AssignParkingItem(String, Option<String>),
+    ResolveParkingItem(String, bool),
+    DeleteParkingItem(String),
     AddMember(String),
+    AddMembers(Vec<String>),
+    SetReactions(Vec<Reaction>),
+    ResetReactions,
+    SetReactionReset(Option<ReactionReset>),
     RemoveMember(String),
+    SetAttendance(String, Attendance),
+    SetMemberNotes(String, MemberNotes),
     NewLeader,
+    SetLeaderStrategy(LeaderStrategy),
     ShuffleMembers,
+    OrderMembers(OrderStrategy),
+    PinMember(String, MemberPin),
+    MoveMember(String, usize),
+    SetSpeakerTime(u64),
+    StartTimer,
+    PauseTimer,
+    NextSpeaker,
+    PreviousSpeaker,
+    JumpToSpeaker(String),
+    StartPokerRound(String),
+    CastVote(String, String),
+    RevealVotes,
+    Revote,
+    EndPokerRound,
+    /// Deadline in unix milliseconds.
+    StartAsyncStandup(u64),
+    SubmitAsyncUpdate(String, MemberNotes, String),
+    CloseAsyncStandup,
 }
 
-async fn start_meeting(query_id: Option<String>, current: &MeetingState, api: &API) -> MeetingState {
-
-    let meeting_id = match query_id {
-        Some(qid) =>  Some(qid),
-        None =>  get_meeting_id(),
-    };
-
+async fn start_meeting(meeting_id: Option<String>, current: &MeetingState, api: &API) -> MeetingState {
     let result: MeetingResult = match meeting_id {
         Some(mid) => api.fetch_meeting(mid).await,
         None => api.create_meeting().await,
@@ -28,13 +53,7 @@ async fn start_meeting(query_id: Option<String>, current: &MeetingState, api: &A
     match result {
         Ok(meeting) => {
             set_meeting_id(&meeting.id);
-            MeetingState {
-                id: Some(meeting.id),
-                leader_id: meeting.leader_id,
-                members: meeting.members,
-                memo: meeting.memo,
-                ..current.clone()
-            }
+            MeetingState::from_meeting(meeting)
         },
         Err(msg) => {
             MeetingState {
@@ -48,36 +67,105 @@ async fn start_meeting(query_id: Option<String>, current: &MeetingState, api: &A
 #[derive(Debug, PartialEq, Clone)]
 pub struct MeetingState {
     pub id: Option<String>,
+    pub version: u64,
     pub leader_id: Option<String>,
+    pub leader_strategy: LeaderStrategy,
+    pub order_strategy: OrderStrategy,
     pub members: Vec<Member>,
+    pub reactions: Vec<Reaction>,
+    pub reaction_reset: Option<ReactionReset>,
     pub memo: String,
+    pub memo_revision: u64,
+    pub parking_lot: Vec<ParkingItem>,
+    pub current_speaker_id: Option<String>,
+    pub timer: SpeakerTimer,
+    pub poker: Option<PokerRound>,
+    pub async_standup: Option<AsyncStandup>,
+    /// Server clock minus local clock in milliseconds.
+    pub clock_offset_ms: i64,
     pub error_msgs: Option<Vec<ErrorMsg>>
 }
 
+impl MeetingState {
+    fn from_meeting(meeting: Meeting) -> MeetingState {
+        MeetingState {
+            id: Some(meeting.id),
+            version: meeting.version,
+            leader_id: meeting.leader_id,
+            leader_strategy: meeting.leader_strategy,
+            order_strategy: meeting.order_strategy,
+            members: meeting.members,
+            reactions: meeting.reactions,
+            reaction_reset: meeting.reaction_reset,
+            memo: meeting.memo,
+            memo_revision: meeting.memo_revision,
+            parking_lot: meeting.parking_lot,
+            current_speaker_id: meeting.current_speaker_id,
+            clock_offset_ms: meeting.timer.server_time as i64 - js_sys::Date::now() as i64,
+            timer: meeting.timer,
+            poker: meeting.poker,
+            async_standup: meeting.async_standup,
+            error_msgs: None,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct MeetingContext {
    pub state: UseStateHandle<MeetingState>,
    api: Rc<API>,
+   // Newest version applied so far. Kept outside of `state` because
+   // callbacks hold on to the state handle they were created with.
+   latest_version: Rc<RefCell<u64>>,
+   // Memo merging our edits with everyone else's, kept outside of `state` for the same reason.
+   memo: Rc<RefCell<MemoClient>>,
 }
 
 pub enum MeetingStatus {
     Initializing,
+    NotFound,
     Ready,
 }
 
 impl MeetingContext {
-    fn new(state: UseStateHandle<MeetingState>, api: Rc<API>) -> MeetingContext {
+    fn new(state: UseStateHandle<MeetingState>, api: Rc<API>, latest_version: Rc<RefCell<u64>>, memo: Rc<RefCell<MemoClient>>) -> MeetingContext {
         MeetingContext {
             state,
             api,
+            latest_version,
+            memo,
         }
     }
 
     pub fn meeting_status(&self) -> MeetingStatus {
         match self.state.id {
             Some(_) => MeetingStatus::Ready,
-            None => MeetingStatus::Initializing,
+            None => {
+                let not_found = self.state.error_msgs
+                    .iter()
+                    .flatten()
+                    .any(|msg| msg.code() == ErrorCode::NotFound);
+                match not_found {
+                    true => MeetingStatus::NotFound,
+                    false => MeetingStatus::Initializing,
+                }
+            },
+        }
+    }
+
+    fn opened_meeting(&self, new_state: MeetingState) {
+        log::info!("start meeting {:?}", new_state);
+        *self.latest_version.borrow_mut() = new_state.version;
+        self.memo.borrow_mut().reset(new_state.memo_revision, &new_state.memo);
+        if let Some(id) = &new_state.id {
+            let my = self.clone();
+            let func = Box::new(move |result: MeetingResult | {
+                log::info!("subscribe {:?}", result);
+                my.received_meeting_result(result);
+            });
+            self.api.subscribe_meeting(id.to_string(), func);
         }
+        self.state.set(new_state);
     }
 
     fn received_meeting_result(&self, result: MeetingResult) {
@@ -85,44 +173,110 @@ impl MeetingContext {
         match result {
             Ok(meeting) => {
                 log::info!("{:?}", meeting);
+                if meeting.version < *self.latest_version.borrow() {
+                    log::info!("discard stale meeting version {:?}", meeting.version);
+                    return;
+                }
+                *self.latest_version.borrow_mut() = meeting.version;
+                let to_send = self.memo.borrow_mut().receive(&meeting);
+                let memo = self.memo.borrow().text.clone();
                 state.set(MeetingState {
-                    id: Some(meeting.id),
-                    members: meeting.members,
-                    memo: meeting.memo,
-                    leader_id: meeting.leader_id.clone(),
-                    error_msgs: None,
-                })
+                    memo,
+                    ..MeetingState::from_meeting(meeting)
+                });
+                if let Some((revision, ops)) = to_send {
+                    self.send_memo_edit(revision, ops);
+                }
             },
             Err(msg) => {
                 log::error!("{:?}", msg);
+                // Conflicts are kept too: the subscription brings the newer meeting,
+                // but the change that was refused has to be made again.
                 state.set(MeetingState {
-                    id: state.id.clone(),
-                    members: state.members.to_vec(),
-                    memo: state.memo.to_string(),
-                    leader_id: state.leader_id.clone(),
                     error_msgs: Some(msg),
+                    ..(*state).clone()
                 })
             },
         }
     }
 
+    /// Sends our memo edit. When the server refuses it the memo is reloaded,
+    /// dropping whatever was typed meanwhile.
+    fn send_memo_edit(&self, revision: u64, ops: Operation) {
+        if let Some(id) = self.state.id.clone() {
+            let my = self.clone();
+            let client_id = self.memo.borrow().client_id.clone();
+            spawn_local(async move {
+                match my.api.edit_memo(id.clone(), revision, client_id, ops).await {
+                    Ok(meeting) => my.received_meeting_result(Ok(meeting)),
+                    Err(msg) => {
+                        log::error!("{:?}", msg);
+                        let result = my.api.fetch_meeting(id).await;
+                        if let Ok(meeting) = &result {
+                            my.memo.borrow_mut().reset(meeting.memo_revision, &meeting.memo);
+                        }
+                        my.received_meeting_result(result);
+                    },
+                }
+            });
+        }
+    }
+
+    /// Loads leader picks and speaking orders recorded between `from` and `to` (unix ms).
+    pub fn fetch_history(&self, from: Option<u64>, to: Option<u64>, cb: Callback<ApiResult<Vec<HistoryEntry>>>) {
+        if let Some(id) = self.state.id.clone() {
+            let api = Rc::clone(&self.api);
+            spawn_local(async move {
+                cb.emit(api.meeting_history(id, from, to).await);
+            });
+        }
+    }
+
+    /// Loads the closed async standups, newest first.
+    pub fn fetch_async_summaries(&self, cb: Callback<ApiResult<Vec<AsyncSummary>>>) {
+        if let Some(id) = self.state.id.clone() {
+            let api = Rc::clone(&self.api);
+            spawn_local(async move {
+                cb.emit(api.async_summaries(id).await);
+            });
+        }
+    }
+
+    /// Loads the meeting rendered as `format`, e.g. to copy it to the clipboard.
+    pub fn fetch_export(&self, format: ExportFormat, cb: Callback<ApiResult<String>>) {
+        if let Some(id) = self.state.id.clone() {
+            let api = Rc::clone(&self.api);
+            spawn_local(async move {
+                cb.emit(api.export_meeting(id, format).await);
+            });
+        }
+    }
+
+    /// Whether the running poker round was started from this browser, which may reveal or end it.
+    pub fn is_facilitator(&self) -> bool {
+        match (&self.state.poker, get_poker_round_id()) {
+            (Some(poker), Some(round_id)) => poker.id == round_id,
+            _ => false,
+        }
+    }
+
+    /// Address downloading the meeting as a `format` file.
+    pub fn export_url(&self, format: ExportFormat) -> Option<String> {
+        self.state.id.as_ref().map(|id| self.api.export_url(id, format))
+    }
+
     pub fn dispatch(&self, action: MeetingActions) {
         let state = self.state.clone();
         let my = self.clone();
         spawn_local( async move  {
             match action {
                 MeetingActions::StartMeeting(id) => {
-                    let new_state = start_meeting(id, &state, &my.api).await;
-                    log::info!("start meeting {:?}", new_state);
-                    let api = Rc::clone(&my.api);
-                    let func = Box::new(move |result: MeetingResult | {
-                        log::info!("subscribe {:?}", result);
-                        my.received_meeting_result(result);
-                    });
-                    if let Some(id) = &new_state.id {
-                        api.subscribe_meeting(id.to_string(), func);
-                        state.set(new_state);
-                    }
+                    let new_state = start_meeting(id.or_else(get_meeting_id), &state, &my.api).await;
+                    my.opened_meeting(new_state);
+                },
+                MeetingActions::CreateMeeting => {
+                    let new_state = start_meeting(None, &state, &my.api).await;
+                    my.opened_meeting(new_state);
                 },
                 MeetingActions::AddMember(name) => {
                     if let Some(id) = &state.id {
@@ -130,6 +284,30 @@ impl MeetingContext {
                         my.received_meeting_result(result);
                     }
                 },
+                MeetingActions::AddMembers(names) => {
+                    if let Some(id) = &state.id {
+                        let result = my.api.add_members(id.clone(), names).await;
+                        my.received_meeting_result(result);
+                    }
+                },
+                MeetingActions::SetReactions(reactions) => {
+                    if let Some(id) = &state.id {
+                        let result = my.api.set_reactions(id.clone(), reactions).await;
+                        my.received_meeting_result(result);
+                    }
+                },
+                MeetingActions::ResetReactions => {
+                    if let Some(id) = &state.id {
+                        let result = my.api.reset_reactions(id.clone()).await;
+                        my.received_meeting_result(result);
+                    }
+                },
+                MeetingActions::SetReactionReset(schedule) => {
+                    if let Some(id) = &state.id {
+                        let result = my.api.set_reaction_reset(id.clone(), schedule).await;
+                        my.received_meeting_result(result);
+                    }
+                },
                 MeetingActions::RemoveMember(member_id) => {
                     if let Some(id) = &state.id {
                         log::info!("remove member {:?}", member_id);
@@ -140,13 +318,51 @@ impl MeetingContext {
                         my.received_meeting_result(result);
                     }
                 },
-                MeetingActions::UpdateMemo(memo) => {
+                MeetingActions::SetAttendance(member_id, attendance) => {
                     if let Some(id) = &state.id {
-                        log::info!("update memo {:?}", memo);
-                        let result = my.api.update_memo(
-                            id.clone(),
-                            memo,
-                        ).await;
+                        let result = my.api.set_attendance(id.clone(), member_id, attendance).await;
+                        my.received_meeting_result(result);
+                    }
+                },
+                MeetingActions::SetMemberNotes(member_id, notes) => {
+                    if let Some(id) = &state.id {
+                        let result = my.api.set_member_notes(id.clone(), member_id, notes).await;
+                        my.received_meeting_result(result);
+                    }
+                },
+                MeetingActions::EditMemo(memo) => {
+                    let to_send = my.memo.borrow_mut().edit(&memo);
+                    if let Some((revision, ops)) = to_send {
+                        my.send_memo_edit(revision, ops);
+                    }
+                },
+                MeetingActions::AddParkingItem(text, author_id) => {
+                    if let Some(id) = &state.id {
+                        let result = my.api.add_parking_item(id.clone(), text, author_id).await;
+                        my.received_meeting_result(result);
+                    }
+                },
+                MeetingActions::EditParkingItem(item_id, text) => {
+                    if let Some(id) = &state.id {
+                        let result = my.api.edit_parking_item(id.clone(), item_id, text).await;
+                        my.received_meeting_result(result);
+                    }
+                },
+                MeetingActions::AssignParkingItem(item_id, owner_id) => {
+                    if let Some(id) = &state.id {
+                        let result = my.api.assign_parking_item(id.clone(), item_id, owner_id).await;
+                        my.received_meeting_result(result);
+                    }
+                },
+                MeetingActions::ResolveParkingItem(item_id, resolved) => {
+                    if let Some(id) = &state.id {
+                        let result = my.api.resolve_parking_item(id.clone(), item_id, resolved).await;
+                        my.received_meeting_result(result);
+                    }
+                },
+                MeetingActions::DeleteParkingItem(item_id) => {
+                    if let Some(id) = &state.id {
+                        let result = my.api.delete_parking_item(id.clone(), item_id).await;
                         my.received_meeting_result(result);
                     }
                 },
@@ -157,17 +373,127 @@ impl MeetingContext {
                         log::info!("Shffule members");
                     }
                 },
+                MeetingActions::OrderMembers(strategy) => {
+                    if let Some(id) = &state.id {
+                        let result = my.api.order_members(id.clone(), strategy).await;
+                        my.received_meeting_result(result);
+                    }
+                },
+                MeetingActions::PinMember(member_id, pin) => {
+                    if let Some(id) = &state.id {
+                        let result = my.api.pin_member(id.clone(), member_id, pin).await;
+                        my.received_meeting_result(result);
+                    }
+                },
+                MeetingActions::MoveMember(member_id, to_index) => {
+                    if let Some(id) = &state.id {
+                        let result = my.api.move_member(id.clone(), member_id, to_index).await;
+                        my.received_meeting_result(result);
+                    }
+                },
                 MeetingActions::NewLeader => {
                     if let Some(id) = &state.id {
-                        log::info!("New leader");
                         let result = my.api.new_leader(id.clone()).await;
                         my.received_meeting_result(result);
                     }
                 },
+                MeetingActions::SetLeaderStrategy(strategy) => {
+                    if let Some(id) = &state.id {
+                        let result = my.api.set_leader_strategy(id.clone(), strategy).await;
+                        my.received_meeting_result(result);
+                    }
+                },
+                MeetingActions::SetSpeakerTime(seconds) => {
+                    if let Some(id) = &state.id {
+                        let result = my.api.set_speaker_time(id.clone(), seconds).await;
+                        my.received_meeting_result(result);
+                    }
+                },
+                MeetingActions::StartTimer => {
+                    if let Some(id) = &state.id {
+                        let result = my.api.start_timer(id.clone()).await;
+                        my.received_meeting_result(result);
+                    }
+                },
+                MeetingActions::PauseTimer => {
+                    if let Some(id) = &state.id {
+                        let result = my.api.pause_timer(id.clone()).await;
+                        my.received_meeting_result(result);
+                    }
+                },
+                MeetingActions::NextSpeaker => {
+                    if let Some(id) = &state.id {
+                        let result = my.api.next_speaker(id.clone()).await;
+                        my.received_meeting_result(result);
+                    }
+                },
+                MeetingActions::PreviousSpeaker => {
+                    if let Some(id) = &state.id {
+                        let result = my.api.previous_speaker(id.clone()).await;
+                        my.received_meeting_result(result);
+                    }
+                },
+                MeetingActions::JumpToSpeaker(member_id) => {
+                    if let Some(id) = &state.id {
+                        let result = my.api.jump_to_speaker(id.clone(), member_id).await;
+                        my.received_meeting_result(result);
+                    }
+                },
+                MeetingActions::StartPokerRound(topic) => {
+                    if let Some(id) = &state.id {
+                        let result = my.api.start_poker_round(id.clone(), topic, get_facilitator_token()).await;
+                        if let Ok(Some(poker)) = result.as_ref().map(|meeting| &meeting.poker) {
+                            set_poker_round_id(&poker.id);
+                        }
+                        my.received_meeting_result(result);
+                    }
+                },
+                MeetingActions::CastVote(member_id, reaction) => {
+                    if let Some(id) = &state.id {
+                        let result = my.api.cast_vote(id.clone(), member_id, reaction).await;
+                        my.received_meeting_result(result);
+                    }
+                },
+                MeetingActions::RevealVotes => {
+                    if let Some(id) = &state.id {
+                        let result = my.api.reveal_votes(id.clone(), get_facilitator_token()).await;
+                        my.received_meeting_result(result);
+                    }
+                },
+                MeetingActions::Revote => {
+                    if let Some(id) = &state.id {
+                        let result = my.api.revote(id.clone(), get_facilitator_token()).await;
+                        my.received_meeting_result(result);
+                    }
+                },
+                MeetingActions::EndPokerRound => {
+                    if let Some(id) = &state.id {
+                        let result = my.api.end_poker_round(id.clone(), get_facilitator_token()).await;
+                        my.received_meeting_result(result);
+                    }
+                },
+                MeetingActions::StartAsyncStandup(deadline) => {
+                    if let Some(id) = &state.id {
+                        let result = my.api.start_async_standup(id.clone(), deadline).await;
+                        my.received_meeting_result(result);
+                    }
+                },
+                MeetingActions::SubmitAsyncUpdate(member_id, notes, reaction) => {
+                    if let Some(id) = &state.id {
+                        let result = my.api.submit_async_update(id.clone(), member_id, notes, reaction).await;
+                        my.received_meeting_result(result);
+                    }
+                },
+                MeetingActions::CloseAsyncStandup => {
+                    if let Some(id) = &state.id {
+                        let result = my.api.close_async_standup(id.clone()).await;
+                        my.received_meeting_result(result);
+                    }
+                },
                 MeetingActions::UpdateMember(member) => {
                     if let Some(id) = &state.id {
-                        log::info!("New leader");
-                        let result = my.api.update_member(id.clone(), member).await;
+                        // Only this member changes, so changes to the rest of the meeting made meanwhile need not be a conflict.
+                        let result = my.api.update_member(id.clone(), member, None).await;
                         my.received_meeting_result(result);
                     }
                 },
@@ -191,15 +517,29 @@ struct APIContainer {
 pub fn meeting_provider(props: &MeetingProviderProps) -> Html {
     let state = use_state(|| MeetingState {
         id: None,
+        version: 0,
         leader_id: None,
+        leader_strategy: LeaderStrategy::Random,
+        order_strategy: OrderStrategy::Shuffle,
         members: Vec::new(),
+        reactions: Vec::new(),
+        reaction_reset: None,
         memo: String::from(""),
+        memo_revision: 0,
+        parking_lot: Vec::new(),
+        current_speaker_id: None,
+        timer: SpeakerTimer::default(),
+        poker: None,
+        async_standup: None,
+        clock_offset_ms: 0,
         error_msgs: None,
     });
     let api_container = use_state(|| APIContainer {
         api: Rc::from(API::new()),
     });
-    let model = MeetingContext::new(state, Rc::clone(&api_container.api));
+    let latest_version = use_mut_ref(|| 0);
+    let memo = use_mut_ref(MemoClient::new);
+    let model = MeetingContext::new(state, Rc::clone(&api_container.api), latest_version, memo);
     {
         let ctx = model.clone();
         use_effect_with_deps(
@@ -29,13 +29,21 @@ pub fn style_provider(props: &StyleProviderProps) -> Html {
         let member_card_style = style!(
             r#"
                width: 190px;
-               height: 162px;
+               min-height: 162px;
                padding: 2px 8px;
                border-radius: 16px;
                background: #2C344A;
                box-shadow:  1px 1px 2px 0 rgba(0,0,0,0.25),
                             inset -2px -2px 4px 0 rgba(0, 0, 0, 0.25),
                             inset 2px 2px 4px 0 rgba(255, 255, 255, 0.2);
+               &.speaking {
+                   box-shadow:  0 0 0 2px #03A688,
+                                inset -2px -2px 4px 0 rgba(0, 0, 0, 0.25),
+                                inset 2px 2px 4px 0 rgba(255, 255, 255, 0.2);
+               }
+               &.absent {
+                   opacity: 0.45;
+               }
             "#
          ).expect("Failed to mount style");
         let member_card = member_card_style.get_class_name().to_string();
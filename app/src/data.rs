@@ -1 +1,3 @@
 pub mod meeting;
+pub mod memo;
+pub mod reaction;
\ No newline at end of file
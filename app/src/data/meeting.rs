@@ -1,57 +1,73 @@
 use std::slice::Iter;
-use self::ReactionType::*;
 
 use serde:: { Serialize, Deserialize };
+use super::memo::MemoEdit;
+
+/// Key of the reaction members start with, accepted whatever the meeting defines.
+pub const NO_REACTION: &str = "NONE";
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
-pub enum ReactionType {
-    NONE,
-    THUMBSUP,
-    THUMBSDOWN,
-    SMILE,
-    CLAP,
-    ZERO,
-    I,
-    II,
-    III,
-    IV,
-    V,
-    VI,
-    VII,
-    VIII,
-    IX,
-    X,
-}
-
-impl ReactionType {
-    pub fn itr() -> Iter<'static, ReactionType> {
-        static REACTIONS: [ReactionType; 16] = [
-            NONE,
-            THUMBSUP,
-            THUMBSDOWN,
-            SMILE,
-            CLAP,
-            ZERO,
-            I,
-            II,
-            III,
-            IV,
-            V,
-            VI,
-            VII,
-            VIII,
-            IX,
-            X,
-        ];
-        REACTIONS.iter()
-    }
+pub struct Reaction {
+    pub key: String,
+    pub symbol: String,
+    pub label: String,
+    /// Story points, only reactions with points count as planning poker votes.
+    pub points: Option<u32>,
 }
 
+/// Local time of day at which the meeting clears its reactions.
 #[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ReactionReset {
+    /// IANA time zone name, e.g. "Europe/Berlin".
+    pub time_zone: String,
+    pub hour: u32,
+    pub minute: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Attendance {
+    Present,
+    Absent,
+    OutOfOffice,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum MemberPin {
+    None,
+    First,
+    Last,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct Member {
     pub id: String,
     pub name: String,
-    pub reaction: ReactionType,
+    /// Key of one of the reactions of the meeting, or `NONE`.
+    pub reaction: String,
+    /// Unix milliseconds when the reaction was picked.
+    pub reaction_at: Option<u64>,
+    pub attendance: Attendance,
+    pub pin: MemberPin,
+    pub notes: MemberNotes,
+}
+
+/// Standup update of a member.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct MemberNotes {
+    pub yesterday: String,
+    pub today: String,
+    pub blockers: String,
+}
+
+
+impl Member {
+    pub fn is_present(&self) -> bool {
+        self.attendance == Attendance::Present
+    }
 }
 
 pub type Members = Vec<Member>;
@@ -60,16 +76,235 @@ pub type Members = Vec<Member>;
 pub struct InputMember {
     pub id: String,
     pub name: String,
-    pub reaction: ReactionType,
+    pub reaction: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum LeaderStrategy {
+    Random,
+    RoundRobin,
+    LeastRecent,
+    WeightedRandom,
+}
+
+impl LeaderStrategy {
+    pub fn itr() -> Iter<'static, LeaderStrategy> {
+        static STRATEGIES: [LeaderStrategy; 4] = [
+            LeaderStrategy::Random,
+            LeaderStrategy::RoundRobin,
+            LeaderStrategy::LeastRecent,
+            LeaderStrategy::WeightedRandom,
+        ];
+        STRATEGIES.iter()
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            LeaderStrategy::Random => "Random",
+            LeaderStrategy::RoundRobin => "Round robin",
+            LeaderStrategy::LeastRecent => "Least recent",
+            LeaderStrategy::WeightedRandom => "Weighted random",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ExportFormat {
+    Markdown,
+    Json,
+    Csv,
+}
+
+impl ExportFormat {
+    pub fn itr() -> Iter<'static, ExportFormat> {
+        static FORMATS: [ExportFormat; 3] = [
+            ExportFormat::Markdown,
+            ExportFormat::Json,
+            ExportFormat::Csv,
+        ];
+        FORMATS.iter()
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "Markdown",
+            ExportFormat::Json => "JSON",
+            ExportFormat::Csv => "CSV",
+        }
+    }
+
+    /// Value of the `format` parameter of the export endpoint.
+    pub fn param(&self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "markdown",
+            ExportFormat::Json => "json",
+            ExportFormat::Csv => "csv",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum OrderStrategy {
+    Shuffle,
+    SeededShuffle,
+    Alphabetical,
+    ReverseYesterday,
+    LeaderLast,
+}
+
+impl OrderStrategy {
+    pub fn itr() -> Iter<'static, OrderStrategy> {
+        static STRATEGIES: [OrderStrategy; 5] = [
+            OrderStrategy::Shuffle,
+            OrderStrategy::SeededShuffle,
+            OrderStrategy::Alphabetical,
+            OrderStrategy::ReverseYesterday,
+            OrderStrategy::LeaderLast,
+        ];
+        STRATEGIES.iter()
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            OrderStrategy::Shuffle => "Shuffle",
+            OrderStrategy::SeededShuffle => "Shuffle of the day",
+            OrderStrategy::Alphabetical => "Alphabetical",
+            OrderStrategy::ReverseYesterday => "Reverse of yesterday",
+            OrderStrategy::LeaderLast => "Leader speaks last",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeakerTimer {
+    pub duration_secs: u64,
+    pub started_at: Option<u64>,
+    pub remaining_ms: i64,
+    pub server_time: u64,
+}
+
+impl SpeakerTimer {
+    pub fn is_running(&self) -> bool {
+        self.started_at.is_some()
+    }
+
+    /// Milliseconds left at server time `now`; negative once the speaker runs over.
+    pub fn remaining_at(&self, now: u64) -> i64 {
+        match self.started_at {
+            Some(started_at) => self.remaining_ms - now.saturating_sub(started_at) as i64,
+            None => self.remaining_ms,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PokerVote {
+    pub member_id: String,
+    pub reaction: String,
+    pub points: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct PokerSummary {
+    pub min: u32,
+    pub max: u32,
+    pub mean: f64,
+    pub median: f64,
+    pub consensus: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PokerRound {
+    pub id: String,
+    pub topic: String,
+    pub started_at: u64,
+    pub revealed: bool,
+    pub voter_ids: Vec<String>,
+    /// `None` until the facilitator reveals them.
+    pub votes: Option<Vec<PokerVote>>,
+    pub summary: Option<PokerSummary>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AsyncSubmission {
+    pub member_id: String,
+    pub submitted_at: u64,
+}
+
+/// Window in which members post their update instead of speaking in a call.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AsyncStandup {
+    pub opened_at: u64,
+    pub deadline: u64,
+    pub submissions: Vec<AsyncSubmission>,
+}
+
+impl AsyncStandup {
+    pub fn has_submitted(&self, member_id: &str) -> bool {
+        self.submissions.iter().any(|s| s.member_id == member_id)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AsyncUpdate {
+    pub member_id: String,
+    pub name: String,
+    /// Symbol of the reaction.
+    pub reaction: Option<String>,
+    pub notes: MemberNotes,
+    /// `None` when the member did not submit.
+    pub submitted_at: Option<u64>,
+}
+
+/// Closed async standup.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AsyncSummary {
+    pub date: String,
+    pub opened_at: u64,
+    pub closed_at: u64,
+    pub updates: Vec<AsyncUpdate>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ParkingItem {
+    pub id: String,
+    pub text: String,
+    pub author_id: Option<String>,
+    pub owner_id: Option<String>,
+    pub created_at: u64,
+    pub resolved: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct Meeting {
     pub id: String,
+    pub version: u64,
     pub leader_id: Option<String>,
+    pub leader_strategy: LeaderStrategy,
+    pub order_strategy: OrderStrategy,
     pub members: Vec<Member>,
+    pub reactions: Vec<Reaction>,
+    pub reaction_reset: Option<ReactionReset>,
     pub memo: String,
+    pub memo_revision: u64,
+    pub memo_edits: Vec<MemoEdit>,
+    pub parking_lot: Vec<ParkingItem>,
+    pub current_speaker_id: Option<String>,
+    pub timer: SpeakerTimer,
+    pub poker: Option<PokerRound>,
+    pub async_standup: Option<AsyncStandup>,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
@@ -89,24 +324,84 @@ pub struct AddMemberHolder {
     pub add_member: Meeting,
 }
 
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AddMembersHolder {
+    pub add_members: Meeting,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SetReactionsHolder {
+    pub set_reactions: Meeting,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ResetReactionsHolder {
+    pub reset_reactions: Meeting,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SetReactionResetHolder {
+    pub set_reaction_reset: Meeting,
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct NewLeaderHolder {
     pub new_leader: Meeting,
 }
 
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SetLeaderStrategyHolder {
+    pub set_leader_strategy: Meeting,
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct ShuffleMembersHolder {
     pub shuffle_members: Meeting,
 }
 
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderMembersHolder {
+    pub order_members: Meeting,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveMemberHolder {
+    pub move_member: Meeting,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PinMemberHolder {
+    pub pin_member: Meeting,
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct RemoveMemberHolder {
     pub remove_member: Meeting,
 }
 
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SetAttendanceHolder {
+    pub set_attendance: Meeting,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SetMemberNotesHolder {
+    pub set_member_notes: Meeting,
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateMemberHolder {
@@ -115,13 +410,203 @@ pub struct UpdateMemberHolder {
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
 #[serde(rename_all = "camelCase")]
-pub struct UpdateMemoHolder {
-    pub update_memo: Meeting,
+pub struct EditMemoHolder {
+    pub edit_memo: Meeting,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AddParkingItemHolder {
+    pub add_parking_item: Meeting,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct EditParkingItemHolder {
+    pub edit_parking_item: Meeting,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AssignParkingItemHolder {
+    pub assign_parking_item: Meeting,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolveParkingItemHolder {
+    pub resolve_parking_item: Meeting,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteParkingItemHolder {
+    pub delete_parking_item: Meeting,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum HistoryKind {
+    Leader,
+    Order,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct HistoryMember {
+    pub id: String,
+    pub name: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEntry {
+    pub kind: HistoryKind,
+    pub recorded_at: u64,
+    pub members: Vec<HistoryMember>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MeetingHistoryHolder {
+    pub meeting_history: Vec<HistoryEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AsyncSummariesHolder {
+    pub async_summaries: Vec<AsyncSummary>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportMeetingHolder {
+    pub export_meeting: String,
+}
+
+/// Value of `extensions.code` sent by the server.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    NotFound,
+    Conflict,
+    StorageUnavailable,
+    ValidationFailed,
+    Forbidden,
+    Internal,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct ErrorExtensions {
+    pub code: Option<ErrorCode>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SetSpeakerTimeHolder {
+    pub set_speaker_time: Meeting,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct StartTimerHolder {
+    pub start_timer: Meeting,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PauseTimerHolder {
+    pub pause_timer: Meeting,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct NextSpeakerHolder {
+    pub next_speaker: Meeting,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviousSpeakerHolder {
+    pub previous_speaker: Meeting,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct JumpToSpeakerHolder {
+    pub jump_to_speaker: Meeting,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct StartPokerRoundHolder {
+    pub start_poker_round: Meeting,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CastVoteHolder {
+    pub cast_vote: Meeting,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RevealVotesHolder {
+    pub reveal_votes: Meeting,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RevoteHolder {
+    pub revote: Meeting,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct EndPokerRoundHolder {
+    pub end_poker_round: Meeting,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct StartAsyncStandupHolder {
+    pub start_async_standup: Meeting,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmitAsyncUpdateHolder {
+    pub submit_async_update: Meeting,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CloseAsyncStandupHolder {
+    pub close_async_standup: Meeting,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
 pub struct  ErrorMsg {
     pub message: String,
+    #[serde(default)]
+    pub extensions: Option<ErrorExtensions>,
+}
+
+impl ErrorMsg {
+    pub fn new(message: &str) -> ErrorMsg {
+        ErrorMsg {
+            message: String::from(message),
+            extensions: None,
+        }
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        self.extensions
+            .as_ref()
+            .and_then(|ext| ext.code.clone())
+            .unwrap_or(ErrorCode::Unknown)
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
@@ -0,0 +1,338 @@
+use serde::{Deserialize, Serialize};
+use super::meeting::Meeting;
+
+/// One step of a memo edit as sent by the server. Exactly one field is set.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoOp {
+    pub retain: Option<u64>,
+    pub insert: Option<String>,
+    pub delete: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoEdit {
+    pub revision: u64,
+    pub client_id: String,
+    pub ops: Vec<MemoOp>,
+}
+
+/// Same steps as on the server, lengths count chars.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Op {
+    Retain(usize),
+    Insert(String),
+    Delete(usize),
+}
+
+pub type Operation = Vec<Op>;
+
+fn push(ops: &mut Operation, op: Op) {
+    match (ops.last_mut(), op) {
+        (_, Op::Retain(0)) | (_, Op::Delete(0)) => (),
+        (_, Op::Insert(text)) if text.is_empty() => (),
+        (Some(Op::Retain(last)), Op::Retain(n)) => *last += n,
+        (Some(Op::Delete(last)), Op::Delete(n)) => *last += n,
+        (Some(Op::Insert(last)), Op::Insert(text)) => last.push_str(&text),
+        (_, op) => ops.push(op),
+    }
+}
+
+fn len(op: &Op) -> usize {
+    match op {
+        Op::Retain(n) | Op::Delete(n) => *n,
+        Op::Insert(text) => text.chars().count(),
+    }
+}
+
+/// What is left of `op` once its first `done` chars were handled.
+fn rest(op: Op, done: usize) -> Option<Op> {
+    match op {
+        Op::Retain(n) if n > done => Some(Op::Retain(n - done)),
+        Op::Delete(n) if n > done => Some(Op::Delete(n - done)),
+        Op::Insert(text) if text.chars().count() > done => Some(Op::Insert(text.chars().skip(done).collect())),
+        _ => None,
+    }
+}
+
+pub fn is_noop(ops: &[Op]) -> bool {
+    ops.iter().all(|op| matches!(op, Op::Retain(_)))
+}
+
+pub fn from_memo_ops(ops: &[MemoOp]) -> Operation {
+    let mut operation = Vec::new();
+    for op in ops {
+        match (op.retain, &op.insert, op.delete) {
+            (Some(n), _, _) => push(&mut operation, Op::Retain(n as usize)),
+            (_, Some(text), _) => push(&mut operation, Op::Insert(text.clone())),
+            (_, _, Some(n)) => push(&mut operation, Op::Delete(n as usize)),
+            _ => (),
+        }
+    }
+    operation
+}
+
+pub fn to_memo_ops(ops: &[Op]) -> Vec<MemoOp> {
+    ops.iter()
+        .map(|op| match op {
+            Op::Retain(n) => MemoOp { retain: Some(*n as u64), insert: None, delete: None },
+            Op::Insert(text) => MemoOp { retain: None, insert: Some(text.clone()), delete: None },
+            Op::Delete(n) => MemoOp { retain: None, insert: None, delete: Some(*n as u64) },
+        })
+        .collect()
+}
+
+/// `None` when `ops` does not span exactly `text`.
+pub fn apply(text: &str, ops: &[Op]) -> Option<String> {
+    let base_len: usize = ops.iter().filter(|op| !matches!(op, Op::Insert(_))).map(len).sum();
+    if base_len != text.chars().count() {
+        return None;
+    }
+    let mut chars = text.chars();
+    let mut result = String::with_capacity(text.len());
+    for op in ops {
+        match op {
+            Op::Retain(n) => result.extend(chars.by_ref().take(*n)),
+            Op::Insert(text) => result.push_str(text),
+            Op::Delete(n) => { chars.by_ref().take(*n).for_each(drop); },
+        }
+    }
+    Some(result)
+}
+
+/// Shortest edit turning `old` into `new`, a single replaced range.
+pub fn diff(old: &str, new: &str) -> Operation {
+    let old_chars: Vec<char> = old.chars().collect();
+    let new_chars: Vec<char> = new.chars().collect();
+    let prefix = old_chars.iter().zip(&new_chars).take_while(|(a, b)| a == b).count();
+    let suffix = old_chars[prefix..]
+        .iter()
+        .rev()
+        .zip(new_chars[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let mut ops = Vec::new();
+    push(&mut ops, Op::Retain(prefix));
+    push(&mut ops, Op::Delete(old_chars.len() - prefix - suffix));
+    push(&mut ops, Op::Insert(new_chars[prefix..new_chars.len() - suffix].iter().collect()));
+    push(&mut ops, Op::Retain(suffix));
+    ops
+}
+
+/// Single operation doing `a` then `b`. `None` when they do not follow each other.
+pub fn compose(a: &[Op], b: &[Op]) -> Option<Operation> {
+    let mut composed = Vec::new();
+    let (mut a_ops, mut b_ops) = (a.iter().cloned(), b.iter().cloned());
+    let (mut op_a, mut op_b) = (a_ops.next(), b_ops.next());
+    loop {
+        match (op_a.take(), op_b.take()) {
+            (None, None) => break,
+            (Some(Op::Delete(n)), other) => {
+                push(&mut composed, Op::Delete(n));
+                op_a = a_ops.next();
+                op_b = other;
+            },
+            (other, Some(Op::Insert(text))) => {
+                push(&mut composed, Op::Insert(text));
+                op_a = other;
+                op_b = b_ops.next();
+            },
+            (Some(left), Some(right)) => {
+                let done = len(&left).min(len(&right));
+                match (&left, &right) {
+                    (Op::Retain(_), Op::Retain(_)) => push(&mut composed, Op::Retain(done)),
+                    (Op::Retain(_), Op::Delete(_)) => push(&mut composed, Op::Delete(done)),
+                    (Op::Insert(text), Op::Retain(_)) => push(&mut composed, Op::Insert(text.chars().take(done).collect())),
+                    // Text inserted by `a` and removed by `b` never shows up.
+                    _ => (),
+                }
+                op_a = rest(left, done).or_else(|| a_ops.next());
+                op_b = rest(right, done).or_else(|| b_ops.next());
+            },
+            _ => return None,
+        }
+    }
+    Some(composed)
+}
+
+/// Same as on the server: `(a', b')` so that `a` then `b'` equals `b` then `a'`.
+/// When both insert at the same place, the text of `a` goes first.
+pub fn transform(a: &[Op], b: &[Op]) -> Option<(Operation, Operation)> {
+    let (mut a_prime, mut b_prime) = (Vec::new(), Vec::new());
+    let (mut a_ops, mut b_ops) = (a.iter().cloned(), b.iter().cloned());
+    let (mut op_a, mut op_b) = (a_ops.next(), b_ops.next());
+    loop {
+        match (op_a.take(), op_b.take()) {
+            (None, None) => break,
+            (Some(Op::Insert(text)), other) => {
+                push(&mut b_prime, Op::Retain(text.chars().count()));
+                push(&mut a_prime, Op::Insert(text));
+                op_a = a_ops.next();
+                op_b = other;
+            },
+            (other, Some(Op::Insert(text))) => {
+                push(&mut a_prime, Op::Retain(text.chars().count()));
+                push(&mut b_prime, Op::Insert(text));
+                op_a = other;
+                op_b = b_ops.next();
+            },
+            (Some(left), Some(right)) => {
+                let done = len(&left).min(len(&right));
+                match (&left, &right) {
+                    (Op::Retain(_), Op::Retain(_)) => {
+                        push(&mut a_prime, Op::Retain(done));
+                        push(&mut b_prime, Op::Retain(done));
+                    },
+                    (Op::Delete(_), Op::Retain(_)) => push(&mut a_prime, Op::Delete(done)),
+                    (Op::Retain(_), Op::Delete(_)) => push(&mut b_prime, Op::Delete(done)),
+                    _ => (),
+                }
+                op_a = rest(left, done).or_else(|| a_ops.next());
+                op_b = rest(right, done).or_else(|| b_ops.next());
+            },
+            _ => return None,
+        }
+    }
+    Some((a_prime, b_prime))
+}
+
+/// Where the char at `index` ends up once `ops` is applied, to keep the caret in place.
+pub fn transform_index(index: usize, ops: &[Op]) -> usize {
+    let mut position = 0;
+    let mut new_index = index;
+    for op in ops {
+        if position > index {
+            break;
+        }
+        match op {
+            Op::Retain(n) => position += n,
+            Op::Insert(text) => {
+                if position < index {
+                    new_index += text.chars().count();
+                }
+            },
+            Op::Delete(n) => {
+                new_index -= (*n).min(index - position);
+                position += n;
+            },
+        }
+    }
+    new_index
+}
+
+/// Char index of a UTF-16 offset, as used by the DOM selection.
+pub fn char_index(text: &str, utf16_offset: usize) -> usize {
+    let mut units = 0;
+    text.chars().take_while(|c| {
+        units += c.len_utf16();
+        units <= utf16_offset
+    }).count()
+}
+
+/// UTF-16 offset of a char index.
+pub fn utf16_offset(text: &str, char_index: usize) -> usize {
+    text.chars().take(char_index).map(char::len_utf16).sum()
+}
+
+/// Local copy of the memo merging the edits of everyone else with our own.
+/// At most one edit is on its way to the server; typing in the meantime is buffered.
+#[derive(Debug, PartialEq, Clone)]
+pub struct MemoClient {
+    /// Picked once per page load so we recognise our own edits coming back.
+    pub client_id: String,
+    /// Last server revision merged in.
+    pub revision: u64,
+    /// Memo as shown, including our edits not acknowledged yet.
+    pub text: String,
+    sent: Option<Operation>,
+    buffer: Option<Operation>,
+}
+
+impl MemoClient {
+    pub fn new() -> MemoClient {
+        MemoClient {
+            client_id: uuid::Uuid::new_v4().to_string(),
+            revision: 0,
+            text: String::new(),
+            sent: None,
+            buffer: None,
+        }
+    }
+
+    /// Drops everything not acknowledged and starts over from the server's memo.
+    pub fn reset(&mut self, revision: u64, text: &str) {
+        self.revision = revision;
+        self.text = text.to_string();
+        self.sent = None;
+        self.buffer = None;
+    }
+
+    /// Records that the memo was changed to `text` locally.
+    /// Returns the base revision and the edit to send, unless an edit is already on its way.
+    pub fn edit(&mut self, text: &str) -> Option<(u64, Operation)> {
+        let ops = diff(&self.text, text);
+        if is_noop(&ops) {
+            return None;
+        }
+        self.text = text.to_string();
+        if self.sent.is_none() {
+            self.sent = Some(ops.clone());
+            return Some((self.revision, ops));
+        }
+        self.buffer = match self.buffer.take() {
+            Some(buffer) => compose(&buffer, &ops),
+            None => Some(ops),
+        };
+        None
+    }
+
+    /// Merges the edits of `meeting` we have not seen yet.
+    /// Returns the buffered edit to send once our previous one came back.
+    pub fn receive(&mut self, meeting: &Meeting) -> Option<(u64, Operation)> {
+        if meeting.memo_revision <= self.revision {
+            return None;
+        }
+        let oldest_base = meeting.memo_revision - meeting.memo_edits.len() as u64;
+        let merged = match self.revision < oldest_base {
+            true => None,
+            false => self.merge(&meeting.memo_edits),
+        };
+        match merged {
+            Some(true) => self.sent.clone().map(|ops| (self.revision, ops)),
+            Some(false) => None,
+            None => {
+                log::warn!("memo edits missed, reloading memo at revision {}", meeting.memo_revision);
+                self.reset(meeting.memo_revision, &meeting.memo);
+                None
+            },
+        }
+    }
+
+    /// Whether our edit was among `edits`, `None` when they do not fit our copy.
+    fn merge(&mut self, edits: &[MemoEdit]) -> Option<bool> {
+        let mut acknowledged = false;
+        let seen = self.revision;
+        for edit in edits.iter().filter(|edit| edit.revision > seen) {
+            if edit.client_id == self.client_id && self.sent.is_some() {
+                self.sent = self.buffer.take();
+                acknowledged = true;
+            } else {
+                let mut ops = from_memo_ops(&edit.ops);
+                if let Some(sent) = &self.sent {
+                    let (sent, remote) = transform(sent, &ops)?;
+                    self.sent = Some(sent);
+                    ops = remote;
+                }
+                if let Some(buffer) = &self.buffer {
+                    let (buffer, remote) = transform(buffer, &ops)?;
+                    self.buffer = Some(buffer);
+                    ops = remote;
+                }
+                self.text = apply(&self.text, &ops)?;
+            }
+            self.revision = edit.revision;
+        }
+        Some(acknowledged)
+    }
+}
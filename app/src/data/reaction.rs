@@ -0,0 +1,136 @@
+use std::slice::Iter;
+use wasm_bindgen::JsValue;
+use super::meeting::{Reaction, ReactionReset, NO_REACTION};
+
+/// Shown for members without a reaction, or with one the meeting no longer defines.
+pub const NO_REACTION_SYMBOL: &str = "\u{1F610}";
+
+/// Symbol of the reaction `key` among `reactions`.
+pub fn reaction_symbol(reactions: &[Reaction], key: &str) -> String {
+    match key == NO_REACTION {
+        true => String::from(NO_REACTION_SYMBOL),
+        false => reactions
+            .iter()
+            .find(|r| r.key == key)
+            .map(|r| r.symbol.clone())
+            .unwrap_or_else(|| String::from(NO_REACTION_SYMBOL)),
+    }
+}
+
+/// One reaction per line, as edited by `parse_reactions`.
+pub fn format_reactions(reactions: &[Reaction]) -> String {
+    reactions.iter().map(|r| {
+        let mut line = r.symbol.clone();
+        if !r.label.is_empty() {
+            line += &format!(" {}", r.label);
+        }
+        if let Some(points) = r.points {
+            if r.symbol != points.to_string() {
+                line += &format!(" = {}", points);
+            }
+        }
+        line
+    }).collect::<Vec<String>>().join("\n")
+}
+
+/// Reactions of `text`, one per line: a symbol, an optional label and optional ` = points`.
+/// A symbol that is a number is worth that many points. Reactions keep the key of the
+/// one in `current` with the same symbol, so members keep them. Repeated symbols are left out.
+pub fn parse_reactions(text: &str, current: &[Reaction]) -> Vec<Reaction> {
+    let mut reactions: Vec<Reaction> = Vec::new();
+    for line in text.lines() {
+        let (line, points) = match line.rsplit_once('=') {
+            Some((rest, points)) => match points.trim().parse::<u32>() {
+                Ok(points) => (rest, Some(points)),
+                Err(_) => (line, None),
+            },
+            None => (line, None),
+        };
+        let line = line.trim();
+        let (symbol, label) = match line.split_once(char::is_whitespace) {
+            Some((symbol, label)) => (symbol, label.trim()),
+            None => (line, ""),
+        };
+        if symbol.is_empty() || reactions.iter().any(|r| r.symbol == symbol) {
+            continue;
+        }
+        let key = current
+            .iter()
+            .find(|r| r.symbol == symbol)
+            .map(|r| r.key.clone())
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        reactions.push(Reaction {
+            key,
+            symbol: symbol.to_string(),
+            label: label.to_string(),
+            points: points.or_else(|| symbol.parse().ok()),
+        });
+    }
+    reactions
+}
+
+/// Time of day of `reset` as `HH:MM`, the format of time inputs.
+pub fn format_reset_time(reset: &ReactionReset) -> String {
+    format!("{:02}:{:02}", reset.hour, reset.minute)
+}
+
+/// Hour and minute of a `HH:MM` time.
+pub fn parse_reset_time(time: &str) -> Option<(u32, u32)> {
+    let (hour, minute) = time.split_once(':')?;
+    let hour: u32 = hour.parse().ok()?;
+    let minute: u32 = minute.get(..2)?.parse().ok()?;
+    match hour < 24 && minute < 60 {
+        true => Some((hour, minute)),
+        false => None,
+    }
+}
+
+/// IANA name of the time zone of the browser, e.g. "Europe/Berlin".
+pub fn browser_time_zone() -> String {
+    let options = js_sys::Intl::DateTimeFormat::new(&js_sys::Array::new(), &js_sys::Object::new()).resolved_options();
+    js_sys::Reflect::get(&options, &JsValue::from_str("timeZone"))
+        .ok()
+        .and_then(|zone| zone.as_string())
+        .unwrap_or_else(|| String::from("UTC"))
+}
+
+/// Local time at which a reaction was picked, for tooltips.
+pub fn reaction_time(reaction_at: u64) -> String {
+    let date = js_sys::Date::new(&JsValue::from_f64(reaction_at as f64));
+    String::from(date.to_locale_string("default", &JsValue::UNDEFINED))
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ReactionPreset {
+    Fibonacci,
+    TShirt,
+    Status,
+}
+
+impl ReactionPreset {
+    pub fn itr() -> Iter<'static, ReactionPreset> {
+        static PRESETS: [ReactionPreset; 3] = [
+            ReactionPreset::Fibonacci,
+            ReactionPreset::TShirt,
+            ReactionPreset::Status,
+        ];
+        PRESETS.iter()
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ReactionPreset::Fibonacci => "Fibonacci",
+            ReactionPreset::TShirt => "T-shirt sizes",
+            ReactionPreset::Status => "Status",
+        }
+    }
+
+    /// Lines to fill the editor with.
+    pub fn text(&self) -> &'static str {
+        match self {
+            ReactionPreset::Fibonacci => "0\n1\n2\n3\n5\n8\n13\n21\n?",
+            ReactionPreset::TShirt => "XS = 1\nS = 2\nM = 3\nL = 5\nXL = 8",
+            ReactionPreset::Status => "\u{1F44D} good\n\u{1F44E} bad\n\u{1F912} sick\n\u{1F6A7} blocked\n\u{1F334} off",
+        }
+    }
+}
@@ -7,8 +7,9 @@ use yew::prelude::*;
 use components::prepare_members:: { PrepareMembers };
 use components::parking_lot:: { ParkingLot };
 use components::header:: { Header };
-use ctx::styles::{StyleProvider};
-use ctx::meeting::{MeetingProvider, MeetingContext, MeetingStatus};
+use ctx::styles::{StyleProvider, StyleContext};
+use ctx::meeting::{MeetingProvider, MeetingContext, MeetingStatus, MeetingActions};
+use components::typography::{Typography, TextSize};
 
 #[function_component(HeroLoading)]
 fn hero_loading() -> Html {
@@ -26,6 +27,32 @@ fn hero_loading() -> Html {
     )
 }
 
+#[function_component(MeetingNotFound)]
+fn meeting_not_found() -> Html {
+    let meeting_ctx = use_context::<MeetingContext>().expect("no ctx found");
+    let style_ctx = use_context::<StyleContext>().expect("no ctx found");
+    let container = style!(r#"
+        width: 100%;
+        height: 100%;
+        display: flex;
+        flex-direction: column;
+        gap: 16px;
+        justify-content: center;
+        align-items: center;
+    "#).expect("Failed to convert css").get_class_name().to_string();
+    let create_meeting = Callback::from(move |_| {
+        meeting_ctx.dispatch(MeetingActions::CreateMeeting);
+    });
+    html!(
+        <div class={container}>
+            <Typography size={TextSize::H3}>{"This meeting does not exist"}</Typography>
+            <button class={style_ctx.outline_btn.clone()} onclick={create_meeting}>
+                {"Create new meeting"}
+            </button>
+        </div>
+    )
+}
+
 #[function_component(MainContents)]
 fn main_contents() -> Html {
     let root = style!(r#"
@@ -72,6 +99,7 @@ fn app() -> Html {
 
     match meeting_ctx.meeting_status() {
         MeetingStatus::Initializing => html! { <HeroLoading /> }, 
+        MeetingStatus::NotFound => html! { <MeetingNotFound /> },
         MeetingStatus::Ready => html!{ <MainContents /> },
     }
 }
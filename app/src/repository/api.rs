@@ -1,5 +1,5 @@
 use crate::data;
-use crate::data::meeting::{ErrorMsg, AddMemberHolder, Member, RemoveMemberHolder, UpdateMemberHolder, UpdateMemoHolder, ReactionType, ShuffleMembersHolder, NewLeaderHolder};
+use crate::data::meeting::{ErrorMsg, AddMemberHolder, AddMembersHolder, Attendance, Member, RemoveMemberHolder, SetAttendanceHolder, MemberNotes, SetMemberNotesHolder, UpdateMemberHolder, EditMemoHolder, AddParkingItemHolder, EditParkingItemHolder, AssignParkingItemHolder, ResolveParkingItemHolder, DeleteParkingItemHolder, Reaction, SetReactionsHolder, ReactionReset, ResetReactionsHolder, SetReactionResetHolder, ShuffleMembersHolder, OrderMembersHolder, OrderStrategy, MemberPin, PinMemberHolder, MoveMemberHolder, NewLeaderHolder, LeaderStrategy, SetLeaderStrategyHolder, SetSpeakerTimeHolder, StartTimerHolder, PauseTimerHolder, NextSpeakerHolder, PreviousSpeakerHolder, JumpToSpeakerHolder, StartPokerRoundHolder, CastVoteHolder, RevealVotesHolder, RevoteHolder, EndPokerRoundHolder, StartAsyncStandupHolder, SubmitAsyncUpdateHolder, CloseAsyncStandupHolder};
 use crate::repository::gql_protocol::{connection_init_msg, subscribe_msg, RecivedMsg};
 use graphql_client::{GraphQLQuery};
 use wasm_bindgen::prelude::Closure;
@@ -7,7 +7,8 @@ use wasm_bindgen::{JsCast};
 use wasm_bindgen::JsValue;
 use wasm_bindgen_futures::JsFuture;
 use web_sys::{ Request, RequestInit, Response as Res, window, RequestMode, WebSocket, MessageEvent };
-use data::meeting::{Meeting, GQLResponse, MeetingHolder, CreateMeetingHolder };
+use data::memo::{to_memo_ops, Operation};
+use data::meeting::{Meeting, GQLResponse, MeetingHolder, CreateMeetingHolder, HistoryEntry, MeetingHistoryHolder, AsyncSummary, AsyncSummariesHolder, ExportFormat, ExportMeetingHolder };
 
 async fn post(query: serde_json::Value, url:&str) -> JsValue {
     let window = window().unwrap();
@@ -23,28 +24,25 @@ async fn post(query: serde_json::Value, url:&str) -> JsValue {
     JsFuture::from(result_json).await.unwrap()
 }
 
-pub type MeetingResult = Result<Meeting, Vec<ErrorMsg>>;
-type ParseResCB<T> = fn(T) -> Meeting;
-fn parse_response<T>(response: GQLResponse<T>, get_value: ParseResCB<T>)-> MeetingResult {
+pub type ApiResult<R> = Result<R, Vec<ErrorMsg>>;
+pub type MeetingResult = ApiResult<Meeting>;
+type ParseResCB<T, R> = fn(T) -> R;
+fn parse_response<T, R>(response: GQLResponse<T>, get_value: ParseResCB<T, R>)-> ApiResult<R> {
     if let Some(data) = response.data {
-        let meeting = get_value(data);
-        return Ok(meeting);
+        let value = get_value(data);
+        return Ok(value);
     }
     if let Some(data) = response.errors {
         return Err(data);
     }
     if let Some(data) = response.error {
         return Err(Vec::from([
-            ErrorMsg {
-                message: data,
-            },
+            ErrorMsg::new(&data),
         ]));
     }
     Err(Vec::from(
         [
-            ErrorMsg {
-                message: String::from("Unexpected error occured"),
-            }
+            ErrorMsg::new("Unexpected error occured"),
         ]
     ))
 }
@@ -52,7 +50,7 @@ fn parse_response<T>(response: GQLResponse<T>, get_value: ParseResCB<T>)-> Meeti
 #[derive(GraphQLQuery)]
 #[graphql(
     schema_path = "schemas/schema.graphql",
-    query_path = "src/repository/gql/fetch_meeting.graphql",
+    query_path = "src/repository/gql/meeting.graphql",
     response_derives = "Debug"
 )]
 struct FetchMeeting;
@@ -60,7 +58,31 @@ struct FetchMeeting;
 #[derive(GraphQLQuery)]
 #[graphql(
     schema_path = "schemas/schema.graphql",
-    query_path = "src/repository/gql/create_meeting.graphql",
+    query_path = "src/repository/gql/meeting_history.graphql",
+    response_derives = "Debug"
+)]
+struct MeetingHistory;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schemas/schema.graphql",
+    query_path = "src/repository/gql/export_meeting.graphql",
+    response_derives = "Debug"
+)]
+struct ExportMeeting;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schemas/schema.graphql",
+    query_path = "src/repository/gql/async_summaries.graphql",
+    response_derives = "Debug"
+)]
+struct AsyncSummaries;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schemas/schema.graphql",
+    query_path = "src/repository/gql/meeting.graphql",
     response_derives = "Debug"
 )]
 struct CreateMeeting;
@@ -68,25 +90,81 @@ struct CreateMeeting;
 #[derive(GraphQLQuery)]
 #[graphql(
     schema_path = "schemas/schema.graphql",
-    query_path = "src/repository/gql/add_member.graphql",
+    query_path = "src/repository/gql/meeting.graphql",
     response_derives = "Debug"
 )]
 struct AddMember;
 
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schemas/schema.graphql",
+    query_path = "src/repository/gql/meeting.graphql",
+    response_derives = "Debug"
+)]
+struct AddMembers;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schemas/schema.graphql",
+    query_path = "src/repository/gql/meeting.graphql",
+    response_derives = "Debug"
+)]
+struct SetReactions;
 
 #[derive(GraphQLQuery)]
 #[graphql(
     schema_path = "schemas/schema.graphql",
-    query_path = "src/repository/gql/shuffle_members.graphql",
+    query_path = "src/repository/gql/meeting.graphql",
+    response_derives = "Debug"
+)]
+struct ResetReactions;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schemas/schema.graphql",
+    query_path = "src/repository/gql/meeting.graphql",
+    response_derives = "Debug"
+)]
+struct SetReactionReset;
+
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schemas/schema.graphql",
+    query_path = "src/repository/gql/meeting.graphql",
     response_derives = "Debug"
 )]
 struct ShuffleMembers;
 
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schemas/schema.graphql",
+    query_path = "src/repository/gql/meeting.graphql",
+    response_derives = "Debug"
+)]
+struct OrderMembers;
 
 #[derive(GraphQLQuery)]
 #[graphql(
     schema_path = "schemas/schema.graphql",
-    query_path = "src/repository/gql/new_leader.graphql",
+    query_path = "src/repository/gql/meeting.graphql",
+    response_derives = "Debug"
+)]
+struct PinMember;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schemas/schema.graphql",
+    query_path = "src/repository/gql/meeting.graphql",
+    response_derives = "Debug"
+)]
+struct MoveMember;
+
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schemas/schema.graphql",
+    query_path = "src/repository/gql/meeting.graphql",
     response_derives = "Debug"
 )]
 struct NewLeader;
@@ -94,7 +172,15 @@ struct NewLeader;
 #[derive(GraphQLQuery)]
 #[graphql(
     schema_path = "schemas/schema.graphql",
-    query_path = "src/repository/gql/remove_member.graphql",
+    query_path = "src/repository/gql/meeting.graphql",
+    response_derives = "Debug"
+)]
+struct SetLeaderStrategy;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schemas/schema.graphql",
+    query_path = "src/repository/gql/meeting.graphql",
     response_derives = "Debug"
 )]
 struct RemoveMember;
@@ -102,7 +188,23 @@ struct RemoveMember;
 #[derive(GraphQLQuery)]
 #[graphql(
     schema_path = "schemas/schema.graphql",
-    query_path = "src/repository/gql/update_member.graphql",
+    query_path = "src/repository/gql/meeting.graphql",
+    response_derives = "Debug"
+)]
+struct SetAttendance;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schemas/schema.graphql",
+    query_path = "src/repository/gql/meeting.graphql",
+    response_derives = "Debug"
+)]
+struct SetMemberNotes;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schemas/schema.graphql",
+    query_path = "src/repository/gql/meeting.graphql",
     response_derives = "Debug"
 )]
 struct UpdateMember;
@@ -110,15 +212,167 @@ struct UpdateMember;
 #[derive(GraphQLQuery)]
 #[graphql(
     schema_path = "schemas/schema.graphql",
-    query_path = "src/repository/gql/update_memo.graphql",
+    query_path = "src/repository/gql/meeting.graphql",
+    response_derives = "Debug"
+)]
+struct EditMemo;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schemas/schema.graphql",
+    query_path = "src/repository/gql/meeting.graphql",
+    response_derives = "Debug"
+)]
+struct AddParkingItem;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schemas/schema.graphql",
+    query_path = "src/repository/gql/meeting.graphql",
+    response_derives = "Debug"
+)]
+struct EditParkingItem;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schemas/schema.graphql",
+    query_path = "src/repository/gql/meeting.graphql",
     response_derives = "Debug"
 )]
-struct UpdateMemo;
+struct AssignParkingItem;
 
 #[derive(GraphQLQuery)]
 #[graphql(
     schema_path = "schemas/schema.graphql",
-    query_path = "src/repository/gql/subscription_meeting.graphql",
+    query_path = "src/repository/gql/meeting.graphql",
+    response_derives = "Debug"
+)]
+struct ResolveParkingItem;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schemas/schema.graphql",
+    query_path = "src/repository/gql/meeting.graphql",
+    response_derives = "Debug"
+)]
+struct DeleteParkingItem;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schemas/schema.graphql",
+    query_path = "src/repository/gql/meeting.graphql",
+    response_derives = "Debug"
+)]
+struct SetSpeakerTime;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schemas/schema.graphql",
+    query_path = "src/repository/gql/meeting.graphql",
+    response_derives = "Debug"
+)]
+struct StartTimer;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schemas/schema.graphql",
+    query_path = "src/repository/gql/meeting.graphql",
+    response_derives = "Debug"
+)]
+struct PauseTimer;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schemas/schema.graphql",
+    query_path = "src/repository/gql/meeting.graphql",
+    response_derives = "Debug"
+)]
+struct NextSpeaker;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schemas/schema.graphql",
+    query_path = "src/repository/gql/meeting.graphql",
+    response_derives = "Debug"
+)]
+struct PreviousSpeaker;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schemas/schema.graphql",
+    query_path = "src/repository/gql/meeting.graphql",
+    response_derives = "Debug"
+)]
+struct JumpToSpeaker;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schemas/schema.graphql",
+    query_path = "src/repository/gql/meeting.graphql",
+    response_derives = "Debug"
+)]
+struct StartPokerRound;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schemas/schema.graphql",
+    query_path = "src/repository/gql/meeting.graphql",
+    response_derives = "Debug"
+)]
+struct CastVote;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schemas/schema.graphql",
+    query_path = "src/repository/gql/meeting.graphql",
+    response_derives = "Debug"
+)]
+struct RevealVotes;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schemas/schema.graphql",
+    query_path = "src/repository/gql/meeting.graphql",
+    response_derives = "Debug"
+)]
+struct Revote;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schemas/schema.graphql",
+    query_path = "src/repository/gql/meeting.graphql",
+    response_derives = "Debug"
+)]
+struct EndPokerRound;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schemas/schema.graphql",
+    query_path = "src/repository/gql/meeting.graphql",
+    response_derives = "Debug"
+)]
+struct StartAsyncStandup;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schemas/schema.graphql",
+    query_path = "src/repository/gql/meeting.graphql",
+    response_derives = "Debug"
+)]
+struct SubmitAsyncUpdate;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schemas/schema.graphql",
+    query_path = "src/repository/gql/meeting.graphql",
+    response_derives = "Debug"
+)]
+struct CloseAsyncStandup;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schemas/schema.graphql",
+    query_path = "src/repository/gql/meeting.graphql",
     response_derives = "Debug"
 )]
 struct SubscribeMeeting;
@@ -157,21 +411,91 @@ impl API {
         "ws://".to_owned() + &self.origin + "/ws"
     }
 
-    pub async fn update_memo(&self, id: String, memo: String) -> MeetingResult {
-        let variables = update_memo::Variables {
+    pub async fn edit_memo(&self, id: String, base_revision: u64, client_id: String, ops: Operation) -> MeetingResult {
+        let ops = to_memo_ops(&ops)
+            .into_iter()
+            .map(|op| edit_memo::MemoOpInput {
+                retain: op.retain.map(|n| n as i64),
+                insert: op.insert,
+                delete: op.delete.map(|n| n as i64),
+            })
+            .collect();
+        let variables = edit_memo::Variables {
+            id,
+            base_revision: base_revision as i64,
+            client_id,
+            ops,
+        };
+        let build_query = EditMemo::build_query(variables);
+        let query = serde_json::json!(build_query);
+        let json = post(query, &self.url()).await;
+        let response: GQLResponse<EditMemoHolder> = json.into_serde().unwrap();
+        parse_response(response, |d| d.edit_memo)
+    }
+    pub async fn add_parking_item(&self, id: String, text: String, author_id: Option<String>) -> MeetingResult {
+        let variables = add_parking_item::Variables {
+            id,
+            text,
+            author_id,
+        };
+        let build_query = AddParkingItem::build_query(variables);
+        let query = serde_json::json!(build_query);
+        let json = post(query, &self.url()).await;
+        let response: GQLResponse<AddParkingItemHolder> = json.into_serde().unwrap();
+        parse_response(response, |d| d.add_parking_item)
+    }
+
+    pub async fn edit_parking_item(&self, id: String, item_id: String, text: String) -> MeetingResult {
+        let variables = edit_parking_item::Variables {
+            id,
+            item_id,
+            text,
+        };
+        let build_query = EditParkingItem::build_query(variables);
+        let query = serde_json::json!(build_query);
+        let json = post(query, &self.url()).await;
+        let response: GQLResponse<EditParkingItemHolder> = json.into_serde().unwrap();
+        parse_response(response, |d| d.edit_parking_item)
+    }
+
+    pub async fn assign_parking_item(&self, id: String, item_id: String, owner_id: Option<String>) -> MeetingResult {
+        let variables = assign_parking_item::Variables {
+            id,
+            item_id,
+            owner_id,
+        };
+        let build_query = AssignParkingItem::build_query(variables);
+        let query = serde_json::json!(build_query);
+        let json = post(query, &self.url()).await;
+        let response: GQLResponse<AssignParkingItemHolder> = json.into_serde().unwrap();
+        parse_response(response, |d| d.assign_parking_item)
+    }
+
+    pub async fn resolve_parking_item(&self, id: String, item_id: String, resolved: bool) -> MeetingResult {
+        let variables = resolve_parking_item::Variables {
+            id,
+            item_id,
+            resolved,
+        };
+        let build_query = ResolveParkingItem::build_query(variables);
+        let query = serde_json::json!(build_query);
+        let json = post(query, &self.url()).await;
+        let response: GQLResponse<ResolveParkingItemHolder> = json.into_serde().unwrap();
+        parse_response(response, |d| d.resolve_parking_item)
+    }
+
+    pub async fn delete_parking_item(&self, id: String, item_id: String) -> MeetingResult {
+        let variables = delete_parking_item::Variables {
             id,
-            memo,
+            item_id,
         };
-        let build_query = UpdateMemo::build_query(variables);
+        let build_query = DeleteParkingItem::build_query(variables);
         let query = serde_json::json!(build_query);
         let json = post(query, &self.url()).await;
-        let response: GQLResponse<UpdateMemoHolder> = json.into_serde().unwrap();
-        parse_response(
-            response, |
-            d|
-            d.update_memo
-        )
+        let response: GQLResponse<DeleteParkingItemHolder> = json.into_serde().unwrap();
+        parse_response(response, |d| d.delete_parking_item)
     }
+
     pub async fn fetch_meeting(&self, id: String) -> MeetingResult {
         let variables = fetch_meeting::Variables {
             id: id,
@@ -184,31 +508,59 @@ impl API {
         parse_response(response, |d| d.meeting )
     }
 
-    pub async fn update_member(&self, id: String, member: Member) -> MeetingResult {
-        let reaction = match member.reaction {
-            ReactionType::ZERO => update_member::ReactionType::ZERO,
-            ReactionType::I => update_member::ReactionType::I,
-            ReactionType::II => update_member::ReactionType::II,
-            ReactionType::III => update_member::ReactionType::III,
-            ReactionType::IV => update_member::ReactionType::IV,
-            ReactionType::V => update_member::ReactionType::V,
-            ReactionType::VI => update_member::ReactionType::VI,
-            ReactionType::VII => update_member::ReactionType::VII,
-            ReactionType::VIII => update_member::ReactionType::VIII,
-            ReactionType::IX => update_member::ReactionType::IX,
-            ReactionType::X => update_member::ReactionType::X,
-            ReactionType::THUMBSUP => update_member::ReactionType::THUMBSUP,
-            ReactionType::THUMBSDOWN => update_member::ReactionType::THUMBSDOWN,
-            ReactionType::SMILE => update_member::ReactionType::SMILE,
-            ReactionType::CLAP => update_member::ReactionType::CLAP,
-            ReactionType::NONE => update_member::ReactionType::NONE,
+    pub async fn meeting_history(&self, id: String, from: Option<u64>, to: Option<u64>) -> ApiResult<Vec<HistoryEntry>> {
+        let variables = meeting_history::Variables {
+            id,
+            from: from.map(|v| v as i64),
+            to: to.map(|v| v as i64),
         };
+        let build_query = MeetingHistory::build_query(variables);
+        let query = serde_json::json!(build_query);
+        let json = post(query, &self.url()).await;
+        let response: GQLResponse<MeetingHistoryHolder> = json.into_serde().unwrap();
+        parse_response(response, |d| d.meeting_history)
+    }
 
+    pub async fn async_summaries(&self, id: String) -> ApiResult<Vec<AsyncSummary>> {
+        let variables = async_summaries::Variables {
+            id,
+        };
+        let build_query = AsyncSummaries::build_query(variables);
+        let query = serde_json::json!(build_query);
+        let json = post(query, &self.url()).await;
+        let response: GQLResponse<AsyncSummariesHolder> = json.into_serde().unwrap();
+        parse_response(response, |d| d.async_summaries)
+    }
+
+    pub async fn export_meeting(&self, id: String, format: ExportFormat) -> ApiResult<String> {
+        let format = match format {
+            ExportFormat::Markdown => export_meeting::ExportFormat::MARKDOWN,
+            ExportFormat::Json => export_meeting::ExportFormat::JSON,
+            ExportFormat::Csv => export_meeting::ExportFormat::CSV,
+        };
+        let variables = export_meeting::Variables {
+            id,
+            format: Some(format),
+        };
+        let build_query = ExportMeeting::build_query(variables);
+        let query = serde_json::json!(build_query);
+        let json = post(query, &self.url()).await;
+        let response: GQLResponse<ExportMeetingHolder> = json.into_serde().unwrap();
+        parse_response(response, |d| d.export_meeting)
+    }
+
+    /// Address downloading the meeting as a file.
+    pub fn export_url(&self, id: &str, format: ExportFormat) -> String {
+        format!("{}/export/{}?format={}", self.url(), id, format.param())
+    }
+
+    pub async fn update_member(&self, id: String, member: Member, expected_version: Option<u64>) -> MeetingResult {
         let variables = update_member::Variables {
             id,
             member_id: member.id,
-            reaction,
+            reaction: member.reaction,
             name: member.name,
+            expected_version: expected_version.map(|v| v as i64),
         };
         let build_query = UpdateMember::build_query(variables);
         let query = serde_json::json!(build_query);
@@ -229,6 +581,40 @@ impl API {
         parse_response(response, |d| d.remove_member)
     }
 
+    pub async fn set_attendance(&self, id: String, member_id: String, attendance: Attendance) -> MeetingResult {
+        let attendance = match attendance {
+            Attendance::Present => set_attendance::Attendance::PRESENT,
+            Attendance::Absent => set_attendance::Attendance::ABSENT,
+            Attendance::OutOfOffice => set_attendance::Attendance::OUT_OF_OFFICE,
+        };
+        let variables = set_attendance::Variables {
+            id,
+            member_id,
+            attendance,
+        };
+        let build_query = SetAttendance::build_query(variables);
+        let query = serde_json::json!(build_query);
+        let json = post(query, &self.url()).await;
+        let response: GQLResponse<SetAttendanceHolder> = json.into_serde().unwrap();
+        parse_response(response, |d| d.set_attendance)
+    }
+    pub async fn set_member_notes(&self, id: String, member_id: String, notes: MemberNotes) -> MeetingResult {
+        let variables = set_member_notes::Variables {
+            id,
+            member_id,
+            notes: set_member_notes::MemberNotesInput {
+                yesterday: notes.yesterday,
+                today: notes.today,
+                blockers: notes.blockers,
+            },
+        };
+        let build_query = SetMemberNotes::build_query(variables);
+        let query = serde_json::json!(build_query);
+        let json = post(query, &self.url()).await;
+        let response: GQLResponse<SetMemberNotesHolder> = json.into_serde().unwrap();
+        parse_response(response, |d| d.set_member_notes)
+    }
+
     pub async fn create_meeting(&self) -> MeetingResult {
         let variables = create_meeting::Variables {};
         let build_query = CreateMeeting::build_query(variables);
@@ -249,6 +635,62 @@ impl API {
         let response: GQLResponse<AddMemberHolder> = json.into_serde().unwrap();
         parse_response(response, |d| d.add_member)
     }
+    pub async fn add_members(&self, id: String, names: Vec<String>) -> MeetingResult {
+        let variables = add_members::Variables {
+            id,
+            names,
+        };
+        let build_query = AddMembers::build_query(variables);
+        let query = serde_json::json!(build_query);
+        let json = post(query, &self.url()).await;
+        let response: GQLResponse<AddMembersHolder> = json.into_serde().unwrap();
+        parse_response(response, |d| d.add_members)
+    }
+    pub async fn set_reactions(&self, id: String, reactions: Vec<Reaction>) -> MeetingResult {
+        let reactions = reactions
+            .into_iter()
+            .map(|r| set_reactions::ReactionInput {
+                key: r.key,
+                symbol: r.symbol,
+                label: r.label,
+                points: r.points.map(|n| n as i64),
+            })
+            .collect();
+        let variables = set_reactions::Variables {
+            id,
+            reactions,
+        };
+        let build_query = SetReactions::build_query(variables);
+        let query = serde_json::json!(build_query);
+        let json = post(query, &self.url()).await;
+        let response: GQLResponse<SetReactionsHolder> = json.into_serde().unwrap();
+        parse_response(response, |d| d.set_reactions)
+    }
+    pub async fn reset_reactions(&self, id: String) -> MeetingResult {
+        let variables = reset_reactions::Variables {
+            id,
+        };
+        let build_query = ResetReactions::build_query(variables);
+        let query = serde_json::json!(build_query);
+        let json = post(query, &self.url()).await;
+        let response: GQLResponse<ResetReactionsHolder> = json.into_serde().unwrap();
+        parse_response(response, |d| d.reset_reactions)
+    }
+    pub async fn set_reaction_reset(&self, id: String, schedule: Option<ReactionReset>) -> MeetingResult {
+        let variables = set_reaction_reset::Variables {
+            id,
+            schedule: schedule.map(|s| set_reaction_reset::ReactionResetInput {
+                time_zone: s.time_zone,
+                hour: s.hour as i64,
+                minute: s.minute as i64,
+            }),
+        };
+        let build_query = SetReactionReset::build_query(variables);
+        let query = serde_json::json!(build_query);
+        let json = post(query, &self.url()).await;
+        let response: GQLResponse<SetReactionResetHolder> = json.into_serde().unwrap();
+        parse_response(response, |d| d.set_reaction_reset)
+    }
     pub async fn shuffle_members(&self, id: String) -> MeetingResult {
         let variables = shuffle_members::Variables {
             id,
@@ -260,6 +702,56 @@ impl API {
         parse_response(response, |d| d.shuffle_members)
     }
 
+    pub async fn order_members(&self, id: String, strategy: OrderStrategy) -> MeetingResult {
+        let strategy = match strategy {
+            OrderStrategy::Shuffle => order_members::OrderStrategy::SHUFFLE,
+            OrderStrategy::SeededShuffle => order_members::OrderStrategy::SEEDED_SHUFFLE,
+            OrderStrategy::Alphabetical => order_members::OrderStrategy::ALPHABETICAL,
+            OrderStrategy::ReverseYesterday => order_members::OrderStrategy::REVERSE_YESTERDAY,
+            OrderStrategy::LeaderLast => order_members::OrderStrategy::LEADER_LAST,
+        };
+        let variables = order_members::Variables {
+            id,
+            strategy,
+        };
+        let build_query = OrderMembers::build_query(variables);
+        let query = serde_json::json!(build_query);
+        let json = post(query, &self.url()).await;
+        let response: GQLResponse<OrderMembersHolder> = json.into_serde().unwrap();
+        parse_response(response, |d| d.order_members)
+    }
+
+    pub async fn pin_member(&self, id: String, member_id: String, pin: MemberPin) -> MeetingResult {
+        let pin = match pin {
+            MemberPin::None => pin_member::MemberPin::NONE,
+            MemberPin::First => pin_member::MemberPin::FIRST,
+            MemberPin::Last => pin_member::MemberPin::LAST,
+        };
+        let variables = pin_member::Variables {
+            id,
+            member_id,
+            pin,
+        };
+        let build_query = PinMember::build_query(variables);
+        let query = serde_json::json!(build_query);
+        let json = post(query, &self.url()).await;
+        let response: GQLResponse<PinMemberHolder> = json.into_serde().unwrap();
+        parse_response(response, |d| d.pin_member)
+    }
+
+    pub async fn move_member(&self, id: String, member_id: String, to_index: usize) -> MeetingResult {
+        let variables = move_member::Variables {
+            id,
+            member_id,
+            to_index: to_index as i64,
+        };
+        let build_query = MoveMember::build_query(variables);
+        let query = serde_json::json!(build_query);
+        let json = post(query, &self.url()).await;
+        let response: GQLResponse<MoveMemberHolder> = json.into_serde().unwrap();
+        parse_response(response, |d| d.move_member)
+    }
+
     pub async fn new_leader(&self, id: String) -> MeetingResult {
         let variables = new_leader::Variables {
             id,
@@ -271,6 +763,192 @@ impl API {
         parse_response(response, |d| d.new_leader)
     }
 
+    pub async fn set_leader_strategy(&self, id: String, strategy: LeaderStrategy) -> MeetingResult {
+        let strategy = match strategy {
+            LeaderStrategy::Random => set_leader_strategy::LeaderStrategy::RANDOM,
+            LeaderStrategy::RoundRobin => set_leader_strategy::LeaderStrategy::ROUND_ROBIN,
+            LeaderStrategy::LeastRecent => set_leader_strategy::LeaderStrategy::LEAST_RECENT,
+            LeaderStrategy::WeightedRandom => set_leader_strategy::LeaderStrategy::WEIGHTED_RANDOM,
+        };
+        let variables = set_leader_strategy::Variables {
+            id,
+            strategy,
+        };
+        let build_query = SetLeaderStrategy::build_query(variables);
+        let query = serde_json::json!(build_query);
+        let json = post(query, &self.url()).await;
+        let response: GQLResponse<SetLeaderStrategyHolder> = json.into_serde().unwrap();
+        parse_response(response, |d| d.set_leader_strategy)
+    }
+
+    pub async fn set_speaker_time(&self, id: String, seconds: u64) -> MeetingResult {
+        let variables = set_speaker_time::Variables {
+            id,
+            seconds: seconds as i64,
+        };
+        let build_query = SetSpeakerTime::build_query(variables);
+        let query = serde_json::json!(build_query);
+        let json = post(query, &self.url()).await;
+        let response: GQLResponse<SetSpeakerTimeHolder> = json.into_serde().unwrap();
+        parse_response(response, |d| d.set_speaker_time)
+    }
+
+    pub async fn start_timer(&self, id: String) -> MeetingResult {
+        let variables = start_timer::Variables {
+            id,
+        };
+        let build_query = StartTimer::build_query(variables);
+        let query = serde_json::json!(build_query);
+        let json = post(query, &self.url()).await;
+        let response: GQLResponse<StartTimerHolder> = json.into_serde().unwrap();
+        parse_response(response, |d| d.start_timer)
+    }
+
+    pub async fn pause_timer(&self, id: String) -> MeetingResult {
+        let variables = pause_timer::Variables {
+            id,
+        };
+        let build_query = PauseTimer::build_query(variables);
+        let query = serde_json::json!(build_query);
+        let json = post(query, &self.url()).await;
+        let response: GQLResponse<PauseTimerHolder> = json.into_serde().unwrap();
+        parse_response(response, |d| d.pause_timer)
+    }
+
+    pub async fn next_speaker(&self, id: String) -> MeetingResult {
+        let variables = next_speaker::Variables {
+            id,
+        };
+        let build_query = NextSpeaker::build_query(variables);
+        let query = serde_json::json!(build_query);
+        let json = post(query, &self.url()).await;
+        let response: GQLResponse<NextSpeakerHolder> = json.into_serde().unwrap();
+        parse_response(response, |d| d.next_speaker)
+    }
+
+    pub async fn previous_speaker(&self, id: String) -> MeetingResult {
+        let variables = previous_speaker::Variables {
+            id,
+        };
+        let build_query = PreviousSpeaker::build_query(variables);
+        let query = serde_json::json!(build_query);
+        let json = post(query, &self.url()).await;
+        let response: GQLResponse<PreviousSpeakerHolder> = json.into_serde().unwrap();
+        parse_response(response, |d| d.previous_speaker)
+    }
+
+    pub async fn jump_to_speaker(&self, id: String, member_id: String) -> MeetingResult {
+        let variables = jump_to_speaker::Variables {
+            id,
+            member_id,
+        };
+        let build_query = JumpToSpeaker::build_query(variables);
+        let query = serde_json::json!(build_query);
+        let json = post(query, &self.url()).await;
+        let response: GQLResponse<JumpToSpeakerHolder> = json.into_serde().unwrap();
+        parse_response(response, |d| d.jump_to_speaker)
+    }
+
+    pub async fn start_poker_round(&self, id: String, topic: String, facilitator_token: String) -> MeetingResult {
+        let variables = start_poker_round::Variables {
+            id,
+            topic,
+            facilitator_token,
+        };
+        let build_query = StartPokerRound::build_query(variables);
+        let query = serde_json::json!(build_query);
+        let json = post(query, &self.url()).await;
+        let response: GQLResponse<StartPokerRoundHolder> = json.into_serde().unwrap();
+        parse_response(response, |d| d.start_poker_round)
+    }
+
+    pub async fn cast_vote(&self, id: String, member_id: String, reaction: String) -> MeetingResult {
+        let variables = cast_vote::Variables {
+            id,
+            member_id,
+            reaction,
+        };
+        let build_query = CastVote::build_query(variables);
+        let query = serde_json::json!(build_query);
+        let json = post(query, &self.url()).await;
+        let response: GQLResponse<CastVoteHolder> = json.into_serde().unwrap();
+        parse_response(response, |d| d.cast_vote)
+    }
+
+    pub async fn reveal_votes(&self, id: String, facilitator_token: String) -> MeetingResult {
+        let variables = reveal_votes::Variables {
+            id,
+            facilitator_token,
+        };
+        let build_query = RevealVotes::build_query(variables);
+        let query = serde_json::json!(build_query);
+        let json = post(query, &self.url()).await;
+        let response: GQLResponse<RevealVotesHolder> = json.into_serde().unwrap();
+        parse_response(response, |d| d.reveal_votes)
+    }
+
+    pub async fn revote(&self, id: String, facilitator_token: String) -> MeetingResult {
+        let variables = revote::Variables {
+            id,
+            facilitator_token,
+        };
+        let build_query = Revote::build_query(variables);
+        let query = serde_json::json!(build_query);
+        let json = post(query, &self.url()).await;
+        let response: GQLResponse<RevoteHolder> = json.into_serde().unwrap();
+        parse_response(response, |d| d.revote)
+    }
+
+    pub async fn end_poker_round(&self, id: String, facilitator_token: String) -> MeetingResult {
+        let variables = end_poker_round::Variables {
+            id,
+            facilitator_token,
+        };
+        let build_query = EndPokerRound::build_query(variables);
+        let query = serde_json::json!(build_query);
+        let json = post(query, &self.url()).await;
+        let response: GQLResponse<EndPokerRoundHolder> = json.into_serde().unwrap();
+        parse_response(response, |d| d.end_poker_round)
+    }
+    pub async fn start_async_standup(&self, id: String, deadline: u64) -> MeetingResult {
+        let variables = start_async_standup::Variables {
+            id,
+            deadline: deadline as i64,
+        };
+        let build_query = StartAsyncStandup::build_query(variables);
+        let query = serde_json::json!(build_query);
+        let json = post(query, &self.url()).await;
+        let response: GQLResponse<StartAsyncStandupHolder> = json.into_serde().unwrap();
+        parse_response(response, |d| d.start_async_standup)
+    }
+    pub async fn submit_async_update(&self, id: String, member_id: String, notes: MemberNotes, reaction: String) -> MeetingResult {
+        let variables = submit_async_update::Variables {
+            id,
+            member_id,
+            notes: submit_async_update::MemberNotesInput {
+                yesterday: notes.yesterday,
+                today: notes.today,
+                blockers: notes.blockers,
+            },
+            reaction,
+        };
+        let build_query = SubmitAsyncUpdate::build_query(variables);
+        let query = serde_json::json!(build_query);
+        let json = post(query, &self.url()).await;
+        let response: GQLResponse<SubmitAsyncUpdateHolder> = json.into_serde().unwrap();
+        parse_response(response, |d| d.submit_async_update)
+    }
+    pub async fn close_async_standup(&self, id: String) -> MeetingResult {
+        let variables = close_async_standup::Variables {
+            id,
+        };
+        let build_query = CloseAsyncStandup::build_query(variables);
+        let query = serde_json::json!(build_query);
+        let json = post(query, &self.url()).await;
+        let response: GQLResponse<CloseAsyncStandupHolder> = json.into_serde().unwrap();
+        parse_response(response, |d| d.close_async_standup)
+    }
+
     pub fn subscribe_meeting(&self, id: String, mut cb: Box<dyn FnMut(MeetingResult)>)  {
 
         let ws = WebSocket::new_with_str(&self.ws(), "graphql-ws").unwrap();
@@ -11,3 +11,30 @@ pub fn set_meeting_id(mid: &str) {
   let storage = window().unwrap().local_storage().unwrap().unwrap();
   storage.set_item(meeting_id_key, mid).unwrap();
 }
+
+/// Secret proving this browser started a poker round, generated on first use.
+pub fn get_facilitator_token() -> String {
+  let facilitator_token_key = "facilitator_token";
+  let storage = window().unwrap().local_storage().unwrap().unwrap();
+  match storage.get_item(facilitator_token_key).unwrap() {
+    Some(token) => token,
+    None => {
+      let token = uuid::Uuid::new_v4().to_string();
+      storage.set_item(facilitator_token_key, &token).unwrap();
+      token
+    },
+  }
+}
+
+/// Id of the last poker round started from this browser.
+pub fn get_poker_round_id() -> Option<String> {
+  let poker_round_id_key = "poker_round_id";
+  let storage = window().unwrap().local_storage().unwrap().unwrap();
+  return storage.get_item(poker_round_id_key).unwrap();
+}
+
+pub fn set_poker_round_id(round_id: &str) {
+  let poker_round_id_key = "poker_round_id";
+  let storage = window().unwrap().local_storage().unwrap().unwrap();
+  storage.set_item(poker_round_id_key, round_id).unwrap();
+}
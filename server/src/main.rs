@@ -1,15 +1,22 @@
 extern crate redis;
 mod models;
+mod storage;
+use std::{sync::Arc, time::Duration};
 use async_graphql::http::{playground_source, GraphQLPlaygroundConfig};
 use async_graphql::Schema;
 use async_graphql_axum::{GraphQLRequest, GraphQLResponse, GraphQLSubscription};
-use axum::http::{Method};
-use axum::response::{self, IntoResponse};
+use axum::http::{header, Method, StatusCode};
+use axum::response::{self, Headers, IntoResponse};
 use axum::routing::{get};
-use axum::{extract::Extension, Router, Server};
+use axum::{extract::{Extension, Path, Query}, Router, Server};
 use tower_http::cors::{CorsLayer, Origin};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
-use crate::models::meeting::{MeetingSchema, MutationRoot, QueryRoot, SubscriptionRoot, Storage};
+use crate::models::error::MeetingError;
+use crate::models::export::{export_meeting, utc_date, ExportFormat};
+use crate::models::meeting::{MeetingSchema, MutationRoot, QueryRoot, SubscriptionRoot};
+use crate::models::scheduler::spawn_scheduler;
+use crate::models::timer::now_millis;
+use crate::storage::{Storage, spawn_sweeper, memory::MemoryStore, redis_store::RedisStore, sqlite::SqliteStore};
 use serde::Deserialize;
 
 
@@ -23,12 +30,75 @@ async fn graphql_playground() -> impl IntoResponse {
     ))
 }
 
+#[derive(Deserialize)]
+struct ExportParams {
+  #[serde(default)]
+  format: ExportFormat,
+}
+
+/// Serves `exportMeeting` as a download, e.g. `/export/<id>?format=csv`.
+async fn export_handler(
+    Extension(storage): Extension<Storage>,
+    Path(id): Path<String>,
+    Query(params): Query<ExportParams>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let error = |er: MeetingError| {
+        let status = match er {
+            MeetingError::NotFound(_) => StatusCode::NOT_FOUND,
+            MeetingError::StorageUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, er.message())
+    };
+    let meeting = storage.get(&id)
+        .await
+        .map_err(|er| error(er.into()))?
+        .ok_or_else(|| error(MeetingError::NotFound(String::from("Invalid meeting id"))))?;
+    let now = now_millis();
+    let body = export_meeting(&meeting, params.format, now)
+        .map_err(|er| error(MeetingError::Internal(er.to_string())))?;
+    let filename = format!("standup-{}.{}", utc_date(now), params.format.extension());
+    let headers = Headers(vec![
+        (header::CONTENT_TYPE, params.format.content_type().to_string()),
+        (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename)),
+    ]);
+    Ok((headers, body))
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "lowercase")]
+enum StorageKind {
+  Redis,
+  Memory,
+  Sqlite,
+}
+
+fn default_storage() -> StorageKind {
+  StorageKind::Redis
+}
+
 #[derive(Deserialize, Debug)]
 struct EnvConfig {
-  redis_url: String,
+  #[serde(default = "default_storage")]
+  storage: StorageKind,
+  redis_url: Option<String>,
+  sqlite_path: Option<String>,
+  retention_days: Option<u64>,
   allow_origin: String,
 }
 
+/// How long meetings nobody touches are kept. Zero would drop every meeting right after it is saved.
+fn retention(days: u64) -> Duration {
+    if days == 0 {
+        panic!("RETENTION_DAYS must be at least 1, leave it unset to keep meetings forever");
+    }
+    let secs = days
+        .checked_mul(24 * 60 * 60)
+        .filter(|secs| i64::try_from(*secs).is_ok())
+        .expect("RETENTION_DAYS is too large");
+    Duration::from_secs(secs)
+}
+
 #[tokio::main]
 async fn main() {
     let config = match envy::from_env::<EnvConfig>() {
@@ -39,26 +109,42 @@ async fn main() {
     tracing_subscriber::registry()
         .with(tracing_subscriber::EnvFilter::new(
             std::env::var("RUST_LOG")
-                .unwrap_or_else(|_| "tower_http=debug".into()),
+                .unwrap_or_else(|_| "server=info,tower_http=debug".into()),
         ))
         .with(tracing_subscriber::fmt::layer())
         .init();
 
-    let client = redis::Client::open(config.redis_url)
-        .expect("failed to open redis");
+    let retention = config.retention_days.map(retention);
+    let storage: Storage = match config.storage {
+        StorageKind::Redis => {
+            let url = config.redis_url.expect("REDIS_URL is required for redis storage");
+            Arc::new(RedisStore::open(&url, retention).unwrap_or_else(|er| panic!("{}", er)))
+        },
+        StorageKind::Memory => Arc::new(MemoryStore::new()),
+        StorageKind::Sqlite => {
+            let path = config.sqlite_path.expect("SQLITE_PATH is required for sqlite storage");
+            Arc::new(SqliteStore::open(&path).expect("failed to open sqlite"))
+        },
+    };
+    if let Some(retention) = retention {
+        spawn_sweeper(storage.clone(), retention);
+    }
+    spawn_scheduler(storage.clone());
     let schema = Schema::build(QueryRoot, MutationRoot, SubscriptionRoot)
-        .data(Storage::from(client))
+        .data(storage.clone())
         .finish();
     
     let app = Router::new()
         .route("/", get(graphql_playground).post(graphql_handler))
         .route("/ws", GraphQLSubscription::new(schema.clone()))
+        .route("/export/:id", get(export_handler))
         .layer(
             CorsLayer::new()
                 .allow_origin(Origin::exact(config.allow_origin.parse().unwrap()))
                 .allow_methods(vec![Method::GET, Method::POST, Method::OPTIONS, Method::HEAD]),
         )
-        .layer(Extension(schema));
+        .layer(Extension(schema))
+        .layer(Extension(storage));
 
     println!("Playground: http://localhost:7070");
 
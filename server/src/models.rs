@@ -1 +1,15 @@
+pub mod async_standup;
+pub mod error;
+pub mod export;
+pub mod history;
+pub mod leader;
 pub mod meeting;
+pub mod memo;
+pub mod notes;
+pub mod order;
+pub mod parking_lot;
+pub mod poker;
+pub mod reaction;
+pub mod reaction_reset;
+pub mod scheduler;
+pub mod timer;
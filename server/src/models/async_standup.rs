@@ -0,0 +1,206 @@
+use async_graphql::*;
+use serde:: { Serialize, Deserialize };
+use crate::storage::Storage;
+use super::error::MeetingError;
+use super::export::utc_date;
+use super::history::append_capped;
+use super::meeting::{update_meeting, Meeting};
+use super::notes::MemberNotes;
+use super::reaction::NO_REACTION;
+use super::timer::now_millis;
+
+/// Closed async standups remembered per meeting; older ones are dropped.
+pub const ASYNC_SUMMARY_LIMIT: usize = 60;
+/// Longest an async standup may stay open.
+const MAX_WINDOW_MS: u64 = 7 * 24 * 60 * 60 * 1000;
+
+#[derive(Clone, PartialEq, SimpleObject, Serialize, Deserialize)]
+pub struct AsyncSubmission {
+    pub member_id: String,
+    /// Unix milliseconds of the latest submission of the member.
+    pub submitted_at: u64,
+    /// Notes as submitted, later edits of the card do not change them.
+    #[serde(default)]
+    #[graphql(skip)]
+    pub notes: MemberNotes,
+    /// Symbol of the reaction as submitted, `None` without one.
+    #[serde(default)]
+    #[graphql(skip)]
+    pub reaction: Option<String>,
+}
+
+/// Window in which members post their update instead of speaking in a call.
+#[derive(Clone, PartialEq, SimpleObject, Serialize, Deserialize)]
+pub struct AsyncStandup {
+    /// Unix milliseconds when the window was opened.
+    pub opened_at: u64,
+    /// Unix milliseconds after which updates are no longer accepted.
+    pub deadline: u64,
+    /// Members who have submitted, oldest first. Everybody else has not yet.
+    pub submissions: Vec<AsyncSubmission>,
+}
+
+/// Update of one member in a closed async standup.
+#[derive(Clone, PartialEq, SimpleObject, Serialize, Deserialize)]
+pub struct AsyncUpdate {
+    pub member_id: String,
+    /// Name when the standup was closed.
+    pub name: String,
+    /// Symbol of the reaction, `None` without one.
+    pub reaction: Option<String>,
+    /// Empty when the member did not submit.
+    pub notes: MemberNotes,
+    /// `None` when the member did not submit.
+    pub submitted_at: Option<u64>,
+}
+
+/// Closed async standup.
+#[derive(Clone, PartialEq, SimpleObject, Serialize, Deserialize)]
+pub struct AsyncSummary {
+    /// `YYYY-MM-DD` in UTC of when the standup closed.
+    pub date: String,
+    pub opened_at: u64,
+    /// Unix milliseconds, the deadline unless the standup was closed early.
+    pub closed_at: u64,
+    /// Every member in speaking order, including those who did not submit.
+    pub updates: Vec<AsyncUpdate>,
+}
+
+impl AsyncStandup {
+    pub fn new(deadline: u64, now: u64) -> Result<AsyncStandup, MeetingError> {
+        if deadline <= now || deadline - now > MAX_WINDOW_MS {
+            return Err(MeetingError::ValidationFailed(String::from("Deadline must be within the next 7 days")));
+        }
+        Ok(AsyncStandup {
+            opened_at: now,
+            deadline,
+            submissions: Vec::new(),
+        })
+    }
+}
+
+impl Meeting {
+    fn async_standup_mut(&mut self) -> Result<&mut AsyncStandup, MeetingError> {
+        self.async_standup
+            .as_mut()
+            .ok_or_else(|| MeetingError::ValidationFailed(String::from("No async standup is open")))
+    }
+
+    /// Records the update of `member_id` in the open async standup. Members may resubmit until the deadline.
+    pub fn submit_async_update(&mut self, member_id: &str, notes: MemberNotes, reaction: String, now: u64) -> Result<(), MeetingError> {
+        let index = self.members
+            .iter()
+            .position(|m| m.id.as_str() == member_id)
+            .ok_or_else(|| MeetingError::NotFound(String::from("Invalid member id")))?;
+        self.check_reaction(&reaction)?;
+        let symbol = self.reactions
+            .iter()
+            .find(|r| r.key == reaction)
+            .map(|r| r.symbol.clone());
+        let standup = self.async_standup_mut()?;
+        if now >= standup.deadline {
+            return Err(MeetingError::ValidationFailed(String::from("The async standup is past its deadline")));
+        }
+        standup.submissions.retain(|s| s.member_id != member_id);
+        standup.submissions.push(AsyncSubmission {
+            member_id: member_id.to_string(),
+            submitted_at: now,
+            notes: notes.clone(),
+            reaction: symbol,
+        });
+        let member = &mut self.members[index];
+        member.reaction_at = match reaction.as_str() {
+            NO_REACTION => None,
+            _ if member.reaction == reaction => member.reaction_at,
+            _ => Some(now),
+        };
+        member.reaction = reaction;
+        member.notes = notes;
+        Ok(())
+    }
+
+    /// Closes the open async standup into a summary of what every member submitted.
+    pub fn close_async_standup(&mut self, now: u64) -> Result<(), MeetingError> {
+        let standup = self.async_standup
+            .take()
+            .ok_or_else(|| MeetingError::ValidationFailed(String::from("No async standup is open")))?;
+        let closed_at = now.min(standup.deadline);
+        let updates = self.members
+            .iter()
+            .map(|m| {
+                let submission = standup.submissions
+                    .iter()
+                    .find(|s| s.member_id == m.id.as_str());
+                AsyncUpdate {
+                    member_id: m.id.to_string(),
+                    name: m.name.clone(),
+                    reaction: submission.and_then(|s| s.reaction.clone()),
+                    notes: submission.map(|s| s.notes.clone()).unwrap_or_default(),
+                    submitted_at: submission.map(|s| s.submitted_at),
+                }
+            })
+            .collect();
+        append_capped(&mut self.async_summaries, AsyncSummary {
+            date: utc_date(closed_at),
+            opened_at: standup.opened_at,
+            closed_at,
+            updates,
+        }, ASYNC_SUMMARY_LIMIT);
+        Ok(())
+    }
+}
+
+/// Closes the async standup of meeting `id` once its deadline has passed.
+pub async fn close_if_due(storage: &Storage, id: &str) -> Result<(), MeetingError> {
+    let due = |meeting: &Meeting, now: u64| meeting.async_standup.as_ref().is_some_and(|s| s.deadline <= now);
+    match storage.get(id).await? {
+        Some(meeting) if due(&meeting, now_millis()) => {},
+        _ => return Ok(()),
+    }
+    update_meeting(storage, id, None, |m: Meeting| {
+        let mut meeting = m.clone();
+        let now = now_millis();
+        // Someone may have closed it in the meantime.
+        if due(&meeting, now) {
+            meeting.close_async_standup(now)?;
+        }
+        Ok(meeting)
+    }).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notes(today: &str) -> MemberNotes {
+        MemberNotes { today: today.to_string(), ..MemberNotes::default() }
+    }
+
+    #[test]
+    fn summary_keeps_what_was_submitted() {
+        let mut meeting: Meeting = serde_json::from_value(serde_json::json!({
+            "id": "meeting",
+            "memo": "",
+            "members": [
+                { "id": "ada", "name": "Ada", "reaction": NO_REACTION },
+                { "id": "bob", "name": "Bob", "reaction": NO_REACTION },
+            ],
+        })).unwrap();
+        meeting.async_standup = Some(AsyncStandup::new(2_000, 1_000).unwrap());
+        meeting.submit_async_update("ada", notes("Ship it"), String::from("THUMBSUP"), 1_500).unwrap();
+        // Edits after submitting and a reaction reset must not change the standup.
+        meeting.members[0].notes = notes("Something else");
+        meeting.members[0].reaction = NO_REACTION.to_string();
+        meeting.close_async_standup(3_000).unwrap();
+
+        let summary = &meeting.async_summaries[0];
+        assert_eq!(summary.closed_at, 2_000);
+        let ada = &summary.updates[0];
+        assert!(ada.notes == notes("Ship it"));
+        assert_eq!(ada.reaction.as_deref(), Some("\u{1F44D}"));
+        assert_eq!(ada.submitted_at, Some(1_500));
+        let bob = &summary.updates[1];
+        assert!(bob.notes.is_empty() && bob.reaction.is_none() && bob.submitted_at.is_none());
+    }
+}
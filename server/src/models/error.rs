@@ -0,0 +1,63 @@
+use async_graphql::{Error, ErrorExtensions};
+use crate::storage::StoreError;
+
+/// Errors returned by the meeting resolvers.
+/// Each one is sent to clients with a machine readable `extensions.code`.
+#[derive(Debug)]
+pub enum MeetingError {
+    NotFound(String),
+    Conflict { expected: u64, actual: u64 },
+    StorageUnavailable(String),
+    ValidationFailed(String),
+    Forbidden(String),
+    Internal(String),
+}
+
+impl MeetingError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            MeetingError::NotFound(_) => "NOT_FOUND",
+            MeetingError::Conflict { .. } => "CONFLICT",
+            MeetingError::StorageUnavailable(_) => "STORAGE_UNAVAILABLE",
+            MeetingError::ValidationFailed(_) => "VALIDATION_FAILED",
+            MeetingError::Forbidden(_) => "FORBIDDEN",
+            MeetingError::Internal(_) => "INTERNAL",
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            MeetingError::NotFound(msg) => msg.clone(),
+            MeetingError::Conflict { expected, actual } => format!(
+                "Meeting has been changed by someone else (expected version {}, current version {})",
+                expected,
+                actual,
+            ),
+            MeetingError::StorageUnavailable(detail) => format!("Failed to connect storage: {}", detail),
+            MeetingError::ValidationFailed(msg) => msg.clone(),
+            MeetingError::Forbidden(msg) => msg.clone(),
+            MeetingError::Internal(detail) => format!("Unexpected error: {}", detail),
+        }
+    }
+}
+
+impl From<StoreError> for MeetingError {
+    fn from(er: StoreError) -> Self {
+        match er {
+            StoreError::Unavailable(detail) => MeetingError::StorageUnavailable(detail),
+            StoreError::Broken(detail) => MeetingError::Internal(format!("Meeting object is broken: {}", detail)),
+        }
+    }
+}
+
+impl From<MeetingError> for Error {
+    fn from(er: MeetingError) -> Self {
+        Error::new(er.message()).extend_with(|_, e| {
+            e.set("code", er.code());
+            if let MeetingError::Conflict { expected, actual } = er {
+                e.set("expectedVersion", expected);
+                e.set("actualVersion", actual);
+            }
+        })
+    }
+}
@@ -0,0 +1,262 @@
+use async_graphql::*;
+use chrono::DateTime;
+use serde:: { Serialize, Deserialize };
+use super::meeting::{Attendance, Meeting};
+use super::notes::MemberNotes;
+
+#[derive(Enum, Copy, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Markdown,
+    Json,
+    /// One row per member, parking lot item and the memo, told apart by the `kind` column.
+    Csv,
+}
+
+impl ExportFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "text/markdown; charset=utf-8",
+            ExportFormat::Json => "application/json",
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "md",
+            ExportFormat::Json => "json",
+            ExportFormat::Csv => "csv",
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MemberSummary<'a> {
+    position: usize,
+    name: &'a str,
+    attendance: Attendance,
+    /// Symbol of the reaction, `None` without one.
+    reaction: Option<&'a str>,
+    leader: bool,
+    /// `None` when the member wrote none.
+    notes: Option<&'a MemberNotes>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ParkingSummary<'a> {
+    text: &'a str,
+    author: Option<&'a str>,
+    owner: Option<&'a str>,
+    resolved: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MeetingSummary<'a> {
+    id: &'a str,
+    date: String,
+    leader: Option<&'a str>,
+    /// In speaking order.
+    members: Vec<MemberSummary<'a>>,
+    parking_lot: Vec<ParkingSummary<'a>>,
+    memo: &'a str,
+}
+
+/// `YYYY-MM-DD` of unix milliseconds in UTC.
+pub fn utc_date(millis: u64) -> String {
+    let date = i64::try_from(millis)
+        .ok()
+        .and_then(DateTime::from_timestamp_millis)
+        .unwrap_or_default();
+    date.date_naive().to_string()
+}
+
+fn summary(meeting: &Meeting, now: u64) -> MeetingSummary<'_> {
+    let name = |id: &str| meeting.members
+        .iter()
+        .find(|m| m.id.as_str() == id)
+        .map(|m| m.name.as_str());
+    MeetingSummary {
+        id: meeting.id.as_str(),
+        date: utc_date(now),
+        leader: meeting.leader_id.as_deref().and_then(name),
+        members: meeting.members
+            .iter()
+            .enumerate()
+            .map(|(i, m)| MemberSummary {
+                position: i + 1,
+                name: &m.name,
+                attendance: m.attendance,
+                reaction: meeting.reactions
+                    .iter()
+                    .find(|r| r.key == m.reaction)
+                    .map(|r| r.symbol.as_str()),
+                leader: meeting.leader_id.as_deref() == Some(m.id.as_str()),
+                notes: Some(&m.notes).filter(|notes| !notes.is_empty()),
+            })
+            .collect(),
+        parking_lot: meeting.parking_lot
+            .iter()
+            .map(|item| ParkingSummary {
+                text: &item.text,
+                author: item.author_id.as_deref().and_then(name),
+                owner: item.owner_id.as_deref().and_then(name),
+                resolved: item.resolved,
+            })
+            .collect(),
+        memo: &meeting.memo,
+    }
+}
+
+/// Indents every line after the first by `indent` spaces, so multi-line text stays inside its list item.
+fn list_item_text(text: &str, indent: usize) -> String {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| match i == 0 || line.trim().is_empty() {
+            true => line.trim_end().to_string(),
+            false => format!("{}{}", " ".repeat(indent), line.trim_end()),
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn to_markdown(summary: &MeetingSummary) -> String {
+    let mut out = format!("# Standup {}\n\n", summary.date);
+    if let Some(leader) = summary.leader {
+        out += &format!("**Leader:** {}\n\n", leader);
+    }
+    out += "## Speaking order\n\n";
+    for member in &summary.members {
+        let status = match member.attendance {
+            Attendance::Present => "",
+            Attendance::Absent => " (absent)",
+            Attendance::OutOfOffice => " (out of office)",
+        };
+        let reaction = match member.reaction {
+            Some(symbol) => format!(" {}", symbol),
+            None => String::new(),
+        };
+        out += &format!("{}. {}{}{}\n", member.position, member.name, status, reaction);
+        if let Some(notes) = member.notes {
+            for (title, note) in [("Yesterday", &notes.yesterday), ("Today", &notes.today), ("Blockers", &notes.blockers)] {
+                if !note.is_empty() {
+                    out += &format!("   - **{}:** {}\n", title, list_item_text(note, 5));
+                }
+            }
+        }
+    }
+    if !summary.parking_lot.is_empty() {
+        out += "\n## Parking lot\n\n";
+        for item in &summary.parking_lot {
+            let people = [item.author.map(|a| format!("raised by {}", a)), item.owner.map(|o| format!("owner {}", o))]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<String>>();
+            let people = match people.is_empty() {
+                true => String::new(),
+                false => format!(" ({})", people.join(", ")),
+            };
+            out += &format!("- [{}] {}{}\n", if item.resolved { "x" } else { " " }, list_item_text(item.text, 2), people);
+        }
+    }
+    if !summary.memo.trim().is_empty() {
+        out += &format!("\n## Memo\n\n{}\n", summary.memo.trim_end());
+    }
+    out
+}
+
+/// Quotes a CSV cell when needed. Cells spreadsheets would read as a formula get a leading `'`.
+fn csv_cell(value: &str) -> String {
+    let value = match value.starts_with(['=', '+', '-', '@']) {
+        true => format!("'{}", value),
+        false => value.to_string(),
+    };
+    match value.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", value.replace('"', "\"\"")),
+        false => value,
+    }
+}
+
+/// Name of a unit enum variant as it appears in JSON.
+fn variant_name(value: impl Serialize) -> Result<String, serde_json::Error> {
+    Ok(serde_json::to_value(value)?.as_str().unwrap_or_default().to_string())
+}
+
+fn to_csv(summary: &MeetingSummary) -> Result<String, serde_json::Error> {
+    let mut rows = vec![String::from("kind,position,name,attendance,reaction,leader,text,owner,resolved,yesterday,today,blockers")];
+    for member in &summary.members {
+        rows.push([
+            String::from("member"),
+            member.position.to_string(),
+            csv_cell(member.name),
+            variant_name(member.attendance)?,
+            csv_cell(member.reaction.unwrap_or_default()),
+            member.leader.to_string(),
+            String::new(),
+            String::new(),
+            String::new(),
+            csv_cell(member.notes.map(|n| n.yesterday.as_str()).unwrap_or_default()),
+            csv_cell(member.notes.map(|n| n.today.as_str()).unwrap_or_default()),
+            csv_cell(member.notes.map(|n| n.blockers.as_str()).unwrap_or_default()),
+        ].join(","));
+    }
+    for (i, item) in summary.parking_lot.iter().enumerate() {
+        rows.push([
+            String::from("parking"),
+            (i + 1).to_string(),
+            csv_cell(item.author.unwrap_or_default()),
+            String::new(),
+            String::new(),
+            String::new(),
+            csv_cell(item.text),
+            csv_cell(item.owner.unwrap_or_default()),
+            item.resolved.to_string(),
+            String::new(),
+            String::new(),
+            String::new(),
+        ].join(","));
+    }
+    rows.push(format!("memo,,,,,,{},,,,,", csv_cell(summary.memo)));
+    Ok(rows.join("\r\n") + "\r\n")
+}
+
+/// Roster in speaking order, leader, reactions, notes, parking lot and memo of `meeting` as of `now`.
+pub fn export_meeting(meeting: &Meeting, format: ExportFormat, now: u64) -> Result<String, serde_json::Error> {
+    let summary = summary(meeting, now);
+    match format {
+        ExportFormat::Markdown => Ok(to_markdown(&summary)),
+        ExportFormat::Json => serde_json::to_string_pretty(&summary),
+        ExportFormat::Csv => to_csv(&summary),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn utc_date_is_the_calendar_day_in_utc() {
+        assert_eq!(utc_date(0), "1970-01-01");
+        assert_eq!(utc_date(1_709_164_800_000), "2024-02-29");
+        assert_eq!(utc_date(1_709_251_199_999), "2024-02-29");
+        assert_eq!(utc_date(1_709_251_200_000), "2024-03-01");
+    }
+
+    #[test]
+    fn multi_line_text_stays_in_its_list_item() {
+        let mut meeting: Meeting = serde_json::from_value(serde_json::json!({
+            "id": "meeting",
+            "memo": "",
+            "members": [{ "id": "ada", "name": "Ada", "reaction": "NONE" }],
+            "parking_lot": [{ "id": "item", "text": "Release\n\n- date", "author_id": null, "owner_id": null, "created_at": 0, "resolved": false }],
+        })).unwrap();
+        meeting.members[0].notes.today = String::from("Review\r\nShip");
+        let markdown = to_markdown(&summary(&meeting, 0));
+        assert!(markdown.contains("1. Ada\n   - **Today:** Review\n     Ship\n"));
+        assert!(markdown.contains("- [ ] Release\n\n  - date\n"));
+    }
+}
@@ -0,0 +1,75 @@
+use async_graphql::*;
+use serde:: { Serialize, Deserialize };
+use super::meeting::Meeting;
+
+/// Speaking orders remembered per meeting; older ones are dropped.
+pub const ORDER_HISTORY_LIMIT: usize = 100;
+
+/// Speaking order decided by `shuffleMembers` or `orderMembers`.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderSnapshot {
+    pub member_ids: Vec<String>,
+    /// Unix milliseconds when the order was decided.
+    pub recorded_at: u64,
+}
+
+/// Appends `item`, dropping the oldest items beyond `limit`.
+pub fn append_capped<T>(list: &mut Vec<T>, item: T, limit: usize) {
+    list.push(item);
+    let overflow = list.len().saturating_sub(limit);
+    list.drain(..overflow);
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum HistoryKind {
+    /// A leader was picked, the only member is the leader.
+    Leader,
+    /// Members were put in a new speaking order.
+    Order,
+}
+
+#[derive(SimpleObject)]
+pub struct HistoryMember {
+    pub id: String,
+    /// Current name, `None` once the member has been removed.
+    pub name: Option<String>,
+}
+
+#[derive(SimpleObject)]
+pub struct HistoryEntry {
+    pub kind: HistoryKind,
+    /// Unix milliseconds. Clients group entries by their own local date.
+    pub recorded_at: u64,
+    pub members: Vec<HistoryMember>,
+}
+
+/// Leader picks and speaking orders recorded in `[from, to)`, oldest first.
+pub fn meeting_history(meeting: &Meeting, from: Option<u64>, to: Option<u64>) -> Vec<HistoryEntry> {
+    let in_range = |at: u64| from.is_none_or(|from| at >= from) && to.is_none_or(|to| at < to);
+    let member = |id: &str| HistoryMember {
+        id: id.to_string(),
+        name: meeting.members
+            .iter()
+            .find(|m| m.id.as_str() == id)
+            .map(|m| m.name.clone()),
+    };
+    let leaders = meeting.leader_history
+        .iter()
+        .filter(|term| in_range(term.chosen_at))
+        .map(|term| HistoryEntry {
+            kind: HistoryKind::Leader,
+            recorded_at: term.chosen_at,
+            members: vec![member(&term.member_id)],
+        });
+    let orders = meeting.order_history
+        .iter()
+        .filter(|order| in_range(order.recorded_at))
+        .map(|order| HistoryEntry {
+            kind: HistoryKind::Order,
+            recorded_at: order.recorded_at,
+            members: order.member_ids.iter().map(|id| member(id)).collect(),
+        });
+    let mut entries: Vec<HistoryEntry> = leaders.chain(orders).collect();
+    entries.sort_by_key(|entry| entry.recorded_at);
+    entries
+}
@@ -0,0 +1,132 @@
+use async_graphql::*;
+use rand::{prelude::SliceRandom, Rng};
+use serde:: { Serialize, Deserialize };
+use super::meeting::Member;
+
+/// Terms remembered per meeting; older ones are dropped.
+pub const LEADER_HISTORY_LIMIT: usize = 100;
+
+/// How `newLeader` picks today's leader among the present members.
+#[derive(Enum, Copy, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum LeaderStrategy {
+    /// Anyone, with equal chances.
+    #[default]
+    Random,
+    /// The member after the current leader in roster order.
+    RoundRobin,
+    /// Whoever has not led for the longest time, members who never led first.
+    LeastRecent,
+    /// Random, but members who led less often get more chances.
+    WeightedRandom,
+}
+
+/// One pick of `newLeader`.
+#[derive(Clone, PartialEq, SimpleObject, Serialize, Deserialize)]
+pub struct LeaderTerm {
+    pub member_id: String,
+    /// Unix milliseconds when the member was picked.
+    pub chosen_at: u64,
+}
+
+/// Picks the next leader among the present members of `roster`, which is in roster order.
+pub fn pick_leader(
+    strategy: LeaderStrategy,
+    roster: &[Member],
+    current_leader: Option<&str>,
+    history: &[LeaderTerm],
+    rng: &mut impl Rng,
+) -> Option<String> {
+    let members: Vec<&Member> = roster.iter().filter(|m| m.is_present()).collect();
+    let terms = |member: &Member| history.iter().filter(|t| t.member_id == member.id.as_str()).count();
+    let last_term = |member: &Member| history
+        .iter()
+        .rev()
+        .find(|t| t.member_id == member.id.as_str())
+        .map(|t| t.chosen_at);
+    // Nobody should lead twice in a row while someone else is around.
+    let candidates: Vec<&Member> = match members.len() {
+        0 | 1 => members.to_vec(),
+        _ => members.iter().copied().filter(|m| Some(m.id.as_str()) != current_leader).collect(),
+    };
+    let leader = match strategy {
+        LeaderStrategy::Random => members.choose(rng).copied(),
+        LeaderStrategy::RoundRobin => {
+            // Walk the whole roster, so an absent leader still hands over to whoever follows them.
+            let next = current_leader
+                .and_then(|leader| roster.iter().position(|m| m.id.as_str() == leader))
+                .and_then(|i| roster
+                    .iter()
+                    .cycle()
+                    .skip(i + 1)
+                    .take(roster.len())
+                    .find(|m| m.is_present()));
+            next.or_else(|| members.first().copied())
+        },
+        LeaderStrategy::LeastRecent => candidates
+            .iter()
+            .copied()
+            .min_by_key(|m| last_term(m)),
+        LeaderStrategy::WeightedRandom => {
+            let most = candidates.iter().map(|m| terms(m)).max().unwrap_or(0);
+            candidates
+                .choose_weighted(rng, |m| most - terms(m) + 1)
+                .ok()
+                .copied()
+        },
+    };
+    leader.map(|m| m.id.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{meeting::Attendance, notes::MemberNotes, order::MemberPin};
+
+    fn member(id: &str, attendance: Attendance) -> Member {
+        Member {
+            id: ID(id.to_string()),
+            name: id.to_string(),
+            reaction: String::from("NONE"),
+            reaction_at: None,
+            attendance,
+            pin: MemberPin::None,
+            notes: MemberNotes::default(),
+        }
+    }
+
+    fn round_robin(roster: &[Member], current_leader: Option<&str>) -> Option<String> {
+        pick_leader(LeaderStrategy::RoundRobin, roster, current_leader, &[], &mut rand::thread_rng())
+    }
+
+    #[test]
+    fn round_robin_picks_the_next_member_and_wraps_around() {
+        let roster = [member("a", Attendance::Present), member("b", Attendance::Present), member("c", Attendance::Present)];
+        assert_eq!(round_robin(&roster, Some("a")).as_deref(), Some("b"));
+        assert_eq!(round_robin(&roster, Some("c")).as_deref(), Some("a"));
+        assert_eq!(round_robin(&roster, None).as_deref(), Some("a"));
+    }
+
+    #[test]
+    fn round_robin_continues_after_an_absent_leader() {
+        let roster = [
+            member("a", Attendance::Present),
+            member("b", Attendance::Absent),
+            member("c", Attendance::OutOfOffice),
+            member("d", Attendance::Present),
+            member("e", Attendance::Present),
+        ];
+        assert_eq!(round_robin(&roster, Some("b")).as_deref(), Some("d"));
+        assert_eq!(round_robin(&roster, Some("e")).as_deref(), Some("a"));
+        let absent_last = [member("a", Attendance::Present), member("b", Attendance::Present), member("c", Attendance::Absent)];
+        assert_eq!(round_robin(&absent_last, Some("c")).as_deref(), Some("a"));
+    }
+
+    #[test]
+    fn round_robin_keeps_a_lone_present_leader() {
+        let roster = [member("a", Attendance::Present), member("b", Attendance::Absent)];
+        assert_eq!(round_robin(&roster, Some("a")).as_deref(), Some("a"));
+        let nobody = [member("a", Attendance::Absent)];
+        assert_eq!(round_robin(&nobody, Some("a")), None);
+    }
+}
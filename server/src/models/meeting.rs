@@ -1,164 +1,359 @@
-use redis::{Commands, RedisError, ErrorKind };
-use rand::prelude::SliceRandom;
 use async_graphql::*;
-use futures::{lock::Mutex, Stream };
-use futures_util::StreamExt as _;
+use futures::{Stream, StreamExt as _};
 use std::{result::Result};
 use serde:: { Serialize, Deserialize };
+use crate::storage::Storage;
+use super::async_standup::{AsyncStandup, AsyncSummary};
+use super::error::MeetingError;
+use super::export::{export_meeting, ExportFormat};
+use super::history::{append_capped, meeting_history, HistoryEntry, OrderSnapshot, ORDER_HISTORY_LIMIT};
+use super::memo::{MemoEdit, MemoOp};
+use super::notes::MemberNotes;
+use super::parking_lot::{validate_text, ParkingItem};
+use super::poker::PokerRound;
+use super::reaction::{default_reactions, validate_reactions, Reaction, NO_REACTION};
+use super::reaction_reset::ReactionReset;
+use super::order::{arrange, order_members, MemberPin, OrderStrategy};
+use super::leader::{pick_leader, LeaderStrategy, LeaderTerm, LEADER_HISTORY_LIMIT};
+use super::timer::{now_millis, SpeakerTimer, MAX_SPEAKER_SECS};
 
 pub type MeetingSchema = Schema<QueryRoot, MutationRoot, SubscriptionRoot>;
-#[derive(Enum, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
-pub enum ReactionType {
-    NONE,
-    THUMBSUP,
-    THUMBSDOWN,
-    SMILE,
-    CLAP,
-    ZERO,
-    I,
-    II,
-    III,
-    IV,
-    V,
-    VI,
-    VII,
-    VIII,
-    IX,
-    X,
+
+/// Most names `addMembers` takes in one call.
+const MAX_MEMBERS_PER_IMPORT: usize = 100;
+/// Whether a member takes part in today's standup.
+#[derive(Enum, Copy, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Attendance {
+    #[default]
+    Present,
+    Absent,
+    OutOfOffice,
 }
 
-#[derive(Clone, SimpleObject, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, SimpleObject, Serialize, Deserialize)]
 pub struct Member {
-    id: ID,
-    name: String,
-    reaction: ReactionType,
+    pub id: ID,
+    pub name: String,
+    /// Key of one of the reactions of the meeting, or `NONE`.
+    pub reaction: String,
+    /// Unix milliseconds when the reaction was picked, `None` without one.
+    #[serde(default)]
+    pub reaction_at: Option<u64>,
+    #[serde(default)]
+    pub attendance: Attendance,
+    #[serde(default)]
+    pub pin: MemberPin,
+    #[serde(default)]
+    pub notes: MemberNotes,
+}
+
+impl Member {
+    fn new(name: String) -> Member {
+        Member {
+            id: ID(uuid::Uuid::new_v4().to_string()),
+            name,
+            reaction: NO_REACTION.to_string(),
+            reaction_at: None,
+            attendance: Attendance::Present,
+            pin: MemberPin::None,
+            notes: MemberNotes::default(),
+        }
+    }
+
+    pub fn is_present(&self) -> bool {
+        self.attendance == Attendance::Present
+    }
 }
 
 #[derive(InputObject)]
 pub struct InputMember {
     id: ID,
     name: String,
-    reaction: ReactionType,
+    reaction: String,
 }
 
-#[derive(Clone, SimpleObject, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, SimpleObject, Serialize, Deserialize)]
 pub struct Meeting {
-    id: ID,
-    leader_id: Option<String>,
-    members: Vec<Member>,
-    memo: String,
+    pub id: ID,
+    /// Incremented on every save, so clients can drop stale frames.
+    #[serde(default)]
+    pub version: u64,
+    pub leader_id: Option<String>,
+    #[serde(default)]
+    pub leader_strategy: LeaderStrategy,
+    /// Earlier leaders, oldest first.
+    #[serde(default)]
+    pub leader_history: Vec<LeaderTerm>,
+    /// Earlier speaking orders, oldest first. Only served through `meetingHistory`.
+    #[serde(default)]
+    #[graphql(skip)]
+    pub order_history: Vec<OrderSnapshot>,
+    /// Strategy used by `shuffleMembers`, set by `orderMembers`.
+    #[serde(default)]
+    pub order_strategy: OrderStrategy,
+    /// Seed of `SEEDED_SHUFFLE`, the current day when `None`.
+    #[serde(default)]
+    pub order_seed: Option<u64>,
+    pub members: Vec<Member>,
+    /// Reactions members can pick from.
+    #[serde(default = "default_reactions")]
+    pub reactions: Vec<Reaction>,
+    /// When reactions are cleared every day, `None` to keep them until changed.
+    #[serde(default)]
+    pub reaction_reset: Option<ReactionReset>,
+    pub memo: String,
+    /// Number of edits made to the memo so far.
+    #[serde(default)]
+    pub memo_revision: u64,
+    /// Latest memo edits, oldest first, for clients merging them into their own copy.
+    #[serde(default)]
+    pub memo_edits: Vec<MemoEdit>,
+    /// Topics to discuss after the standup, oldest first.
+    #[serde(default)]
+    pub parking_lot: Vec<ParkingItem>,
+    /// Member whose turn it is, `None` before the round starts or after the last speaker.
+    #[serde(default)]
+    pub current_speaker_id: Option<String>,
+    #[serde(default)]
+    pub timer: SpeakerTimer,
+    /// Planning poker round, `None` when no round is running.
+    #[serde(default)]
+    pub poker: Option<PokerRound>,
+    /// Async standup taking updates, `None` while members meet live.
+    #[serde(default)]
+    pub async_standup: Option<AsyncStandup>,
+    /// Closed async standups, oldest first. Only served through `asyncSummaries`.
+    #[serde(default)]
+    #[graphql(skip)]
+    pub async_summaries: Vec<AsyncSummary>,
 }
 
-pub type Storage = Mutex<redis::Client>;
+impl Meeting {
+    /// Id of the member speaking after `member_id`, or of the first member when `None`.
+    /// Members who are not present are skipped.
+    fn member_after(&self, member_id: Option<&str>) -> Option<String> {
+        let next = match member_id {
+            Some(member_id) => self.members
+                .iter()
+                .skip_while(|m| m.id.as_str() != member_id)
+                .skip(1)
+                .find(|m| m.is_present()),
+            None => self.members.iter().find(|m| m.is_present()),
+        };
+        next.map(|m| m.id.to_string())
+    }
+
+    /// Id of the member speaking before `member_id`, or of the last member when `None`.
+    /// The first member stays the first one. Members who are not present are skipped.
+    fn member_before(&self, member_id: Option<&str>) -> Option<String> {
+        let previous = match member_id {
+            Some(member_id) => self.members
+                .iter()
+                .rev()
+                .skip_while(|m| m.id.as_str() != member_id)
+                .skip(1)
+                .find(|m| m.is_present())
+                .or_else(|| self.members.iter().find(|m| m.id.as_str() == member_id && m.is_present())),
+            None => self.members.iter().rev().find(|m| m.is_present()),
+        };
+        previous.map(|m| m.id.to_string())
+    }
+
+    /// Puts members in the order of the meeting's strategy and remembers it.
+    fn reorder(&mut self, now: u64) {
+        self.members = order_members(self, self.order_strategy, self.order_seed, now);
+        let member_ids = self.members.iter().map(|m| m.id.to_string()).collect();
+        append_capped(&mut self.order_history, OrderSnapshot {
+            member_ids,
+            recorded_at: now,
+        }, ORDER_HISTORY_LIMIT);
+    }
+
+    /// Gives the turn to `speaker` along with a fresh timebox.
+    fn hand_over(&mut self, speaker: Option<String>, now: u64) {
+        match speaker {
+            Some(_) => self.timer.reset(now),
+            None => self.timer.stop(),
+        }
+        self.current_speaker_id = speaker;
+    }
+}
 
 pub struct QueryRoot;
 
-pub type RetriveMeetingResult = Result<Meeting, String>;
+pub type RetriveMeetingResult = Result<Meeting, Error>;
 #[Object]
 impl QueryRoot {
     async fn meeting(&self, ctx: &Context<'_>, #[graphql(desc = "id of the meeting")] id: String,) -> RetriveMeetingResult {
-        let storage = ctx.data_unchecked::<Storage>().lock().await;
-        let mut conn = storage
-            .get_connection()
-            .or_else(|_| Err(String::from("Failed to connect storage")))?;
-        let data: String = conn.get(id)
-            .or_else(|_| Err(String::from("Invalid meeting id")))?;
-        print!("This data {:?}", data);
-        let meeting: Meeting = serde_json::from_str(&data)
-            .or_else(|_| Err(String::from("failed to convert Meeting")))?;
+        let storage = ctx.data_unchecked::<Storage>();
+        let meeting = storage.get(&id)
+            .await
+            .map_err(MeetingError::from)?
+            .ok_or_else(|| MeetingError::NotFound(String::from("Invalid meeting id")))?;
         Ok(meeting)
     }
+    async fn meeting_history(&self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "id of the meeting")] id: String,
+        #[graphql(desc = "unix milliseconds of the oldest entry")] from: Option<u64>,
+        #[graphql(desc = "unix milliseconds the entries must be older than")] to: Option<u64>,
+    ) -> Result<Vec<HistoryEntry>, Error> {
+        let storage = ctx.data_unchecked::<Storage>();
+        let meeting = storage.get(&id)
+            .await
+            .map_err(MeetingError::from)?
+            .ok_or_else(|| MeetingError::NotFound(String::from("Invalid meeting id")))?;
+        Ok(meeting_history(&meeting, from, to))
+    }
+    /// Closed async standups, newest first.
+    async fn async_summaries(&self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "id of the meeting")] id: String,
+    ) -> Result<Vec<AsyncSummary>, Error> {
+        let storage = ctx.data_unchecked::<Storage>();
+        let meeting = storage.get(&id)
+            .await
+            .map_err(MeetingError::from)?
+            .ok_or_else(|| MeetingError::NotFound(String::from("Invalid meeting id")))?;
+        Ok(meeting.async_summaries.into_iter().rev().collect())
+    }
+    async fn export_meeting(&self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "id of the meeting")] id: String,
+        #[graphql(default)] format: ExportFormat,
+    ) -> Result<String, Error> {
+        let storage = ctx.data_unchecked::<Storage>();
+        let meeting = storage.get(&id)
+            .await
+            .map_err(MeetingError::from)?
+            .ok_or_else(|| MeetingError::NotFound(String::from("Invalid meeting id")))?;
+        let export = export_meeting(&meeting, format, now_millis())
+            .map_err(|er| MeetingError::Internal(er.to_string()))?;
+        Ok(export)
+    }
 }
 
-async fn save_meeting(ctx: &Context<'_>, id: String,mut cb: impl FnMut(Meeting) -> CreateMeetingResult) -> CreateMeetingResult {
-    let storage = ctx.data_unchecked::<Storage>().lock().await;
-
-    let mut conn = storage
-        .get_connection()
-        .or_else(|_| Err(String::from("Failed to connect storage")))?;
-    let cloned_id = id.clone();
-    let result: Result<Meeting, RedisError> = redis::transaction(&mut conn, &[id], move |con, pipe| {
-        let id = &cloned_id;
-        let data: String = con
-            .get(&id)?;
-        let meeting: Meeting = serde_json::from_str(&data).or_else(|_| {
-                Err(
-                    RedisError::from((ErrorKind::TypeError, "Meeting object is broken"))
-                )
-            })?;
-        let new_meeting = cb(meeting).or_else(|_| {
-                Err(
-                    RedisError::from((ErrorKind::TypeError, "Failed to update meeting"))
-                )
-            })?;
-        let json_str: String = serde_json::to_string(&new_meeting)
-            .or_else(|_| {
-                Err(
-                    RedisError::from((ErrorKind::TypeError, "Failed to covert json"))
-                )
-            })?;
-        pipe.set(&id, json_str.clone()).ignore().query(con)?;
-        con.publish::<String, String, i32>(id.to_string(), json_str).unwrap();
-        Ok(Some(new_meeting))
-    });
-    match result {
-        Ok(meeting) =>  Ok(meeting),
-        Err(er) => {
-            let err_msg = match er.detail() {
-                Some(detail) => String::from(detail),
-                None => String::from("Unexpected redis error")
-            };
-            Err(err_msg)
+/// Applies `cb` to the stored meeting and saves the result, retrying when somebody else saved in between.
+pub async fn update_meeting(storage: &Storage, id: &str, expected_version: Option<u64>, mut cb: impl FnMut(Meeting) -> UpdateMeetingResult) -> UpdateMeetingResult {
+    loop {
+        let meeting = storage.get(id)
+            .await?
+            .ok_or_else(|| MeetingError::NotFound(String::from("Invalid meeting id")))?;
+        if let Some(expected) = expected_version {
+            if expected != meeting.version {
+                return Err(MeetingError::Conflict { expected, actual: meeting.version });
+            }
+        }
+        let mut new_meeting = cb(meeting.clone())?;
+        new_meeting.version = meeting.version + 1;
+        let saved = storage.compare_and_set(id, Some(&meeting), &new_meeting).await?;
+        if saved {
+            storage.publish(id, &new_meeting).await?;
+            return Ok(new_meeting);
         }
     }
+}
 
+async fn save_meeting(ctx: &Context<'_>, id: String, expected_version: Option<u64>, cb: impl FnMut(Meeting) -> UpdateMeetingResult) -> CreateMeetingResult {
+    let storage = ctx.data_unchecked::<Storage>();
+    Ok(update_meeting(storage, &id, expected_version, cb).await?)
+}
 
+fn validate_name(name: &str) -> Result<(), MeetingError> {
+    if name.trim().is_empty() {
+        return Err(MeetingError::ValidationFailed(String::from("Member name must not be empty")));
+    }
+    Ok(())
 }
 
-pub type CreateMeetingResult = Result<Meeting, String>;
+pub type CreateMeetingResult = Result<Meeting, Error>;
+pub type UpdateMeetingResult = Result<Meeting, MeetingError>;
 pub struct MutationRoot;
 
 #[Object]
 impl MutationRoot {
     async fn create_meeting(&self, ctx: &Context<'_>) -> CreateMeetingResult {
-        let storage = ctx.data_unchecked::<Storage>().lock().await;
-        let mut conn = storage
-            .get_connection()
-            .expect( "Failed to connect storage");
+        let storage = ctx.data_unchecked::<Storage>();
         let id = uuid::Uuid::new_v4().to_string();
         let meeting = Meeting {
             id: ID(String::from(&id)),
+            version: 0,
             leader_id: None,
+            leader_strategy: LeaderStrategy::default(),
+            leader_history: Vec::new(),
+            order_history: Vec::new(),
+            order_strategy: OrderStrategy::default(),
+            order_seed: None,
             members: Vec::new(),
+            reactions: default_reactions(),
+            reaction_reset: None,
             memo: String::from(""),
+            memo_revision: 0,
+            memo_edits: Vec::new(),
+            parking_lot: Vec::new(),
+            current_speaker_id: None,
+            timer: SpeakerTimer::default(),
+            poker: None,
+            async_standup: None,
+            async_summaries: Vec::new(),
         };
-        let json_str: String = serde_json::to_string(&meeting).expect("failed to conver json");
-        let _: () = conn.set(id, json_str).unwrap();
+        storage.compare_and_set(&id, None, &meeting)
+            .await
+            .map_err(MeetingError::from)?;
         Ok(meeting)
     }
     async fn add_member(&self,
         ctx: &Context<'_>,
         #[graphql(desc = "id of the meeting")] id: String,
+        #[graphql(desc = "version the change is based on, rejected with CONFLICT when outdated")] expected_version: Option<u64>,
         #[graphql(desc = "name of member")] name: String,
     ) -> CreateMeetingResult {
-        save_meeting(ctx, id, move |m| {
+        validate_name(&name)?;
+        save_meeting(ctx, id, expected_version, move |m| {
+            let mut meeting = m.clone();
+            meeting.members.push(Member::new(name.clone()));
+            arrange(&mut meeting.members);
+            Ok(meeting)
+        }).await
+    }
+    async fn add_members(&self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "id of the meeting")] id: String,
+        #[graphql(desc = "version the change is based on, rejected with CONFLICT when outdated")] expected_version: Option<u64>,
+        #[graphql(desc = "names of members, blank names and names already on the board are skipped")] names: Vec<String>,
+    ) -> CreateMeetingResult {
+        let names: Vec<String> = names
+            .iter()
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .collect();
+        if names.is_empty() {
+            return Err(MeetingError::ValidationFailed(String::from("Member names must not be empty")).into());
+        }
+        if names.len() > MAX_MEMBERS_PER_IMPORT {
+            return Err(MeetingError::ValidationFailed(format!("At most {} members can be added at once", MAX_MEMBERS_PER_IMPORT)).into());
+        }
+        save_meeting(ctx, id, expected_version, move |m| {
             let mut meeting = m.clone();
-            let member_id = uuid::Uuid::new_v4().to_string();
-            let member = Member {
-                id: ID(member_id),
-                name: name.clone(),
-                reaction: ReactionType::NONE,
-            };        
-            meeting.members.push(member);
+            for name in &names {
+                let taken = meeting.members.iter().any(|m| m.name.trim().to_lowercase() == name.to_lowercase());
+                if !taken {
+                    meeting.members.push(Member::new(name.clone()));
+                }
+            }
+            arrange(&mut meeting.members);
             Ok(meeting)
         }).await
     }
     async fn update_member(&self,
         ctx: &Context<'_>,
         #[graphql(desc = "id of the meeting")] id: String,
+        #[graphql(desc = "version the change is based on, rejected with CONFLICT when outdated")] expected_version: Option<u64>,
         #[graphql(desc = "struct of member")] member: InputMember,
     ) -> CreateMeetingResult {
-        save_meeting(ctx, id, move |m| {
+        validate_name(&member.name)?;
+        save_meeting(ctx, id, expected_version, move |m| {
             let member_id = member.id.to_string();
             let mut meeting = m.clone();
             let index_result = meeting
@@ -168,97 +363,640 @@ impl MutationRoot {
                 .position(|m| m.id.to_string() == member_id);
             let index = match index_result {
                 Some(i) => i,
-                None => return Err(String::from("Invalid member id"))
+                None => return Err(MeetingError::NotFound(String::from("Invalid member id")))
+            };
+            meeting.check_reaction(&member.reaction)?;
+            let attendance = meeting.members[index].attendance;
+            let pin = meeting.members[index].pin;
+            let notes = meeting.members[index].notes.clone();
+            let reaction_at = match (member.reaction.as_str(), &meeting.members[index]) {
+                (NO_REACTION, _) => None,
+                (reaction, old) if old.reaction == reaction => old.reaction_at,
+                _ => Some(now_millis()),
             };
             let _ = std::mem::replace(
                 &mut meeting.members[index],
                 Member {
                         id: member.id.clone(),
                         name: member.name.clone(),
-                        reaction: member.reaction
+                        reaction: member.reaction.clone(),
+                        reaction_at,
+                        attendance,
+                        pin,
+                        notes,
                     });
             Ok(meeting)
         }).await
     }
+    /// Replaces the reactions members pick from, in display order.
+    async fn set_reactions(&self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "id of the meeting")] id: String,
+        #[graphql(desc = "version the change is based on, rejected with CONFLICT when outdated")] expected_version: Option<u64>,
+        reactions: Vec<Reaction>,
+    ) -> CreateMeetingResult {
+        let reactions: Vec<Reaction> = reactions
+            .into_iter()
+            .map(|r| Reaction {
+                key: r.key,
+                symbol: r.symbol.trim().to_string(),
+                label: r.label.trim().to_string(),
+                points: r.points,
+            })
+            .collect();
+        validate_reactions(&reactions)?;
+        save_meeting(ctx, id, expected_version, move |m: Meeting| -> UpdateMeetingResult {
+            let mut meeting = m.clone();
+            meeting.set_reactions(reactions.clone());
+            Ok(meeting)
+        }).await
+    }
+    /// Clears every member's reaction.
+    async fn reset_reactions(&self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "id of the meeting")] id: String,
+        #[graphql(desc = "version the change is based on, rejected with CONFLICT when outdated")] expected_version: Option<u64>,
+    ) -> CreateMeetingResult {
+        save_meeting(ctx, id, expected_version, move |m: Meeting| -> UpdateMeetingResult {
+            let mut meeting = m.clone();
+            meeting.clear_reactions_before(u64::MAX);
+            Ok(meeting)
+        }).await
+    }
+    /// Clears reactions every day at a local time. Reactions picked before the latest reset
+    /// time are cleared within a minute, including when the schedule is set.
+    async fn set_reaction_reset(&self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "id of the meeting")] id: String,
+        #[graphql(desc = "version the change is based on, rejected with CONFLICT when outdated")] expected_version: Option<u64>,
+        #[graphql(desc = "`null` turns the daily reset off")] schedule: Option<ReactionReset>,
+    ) -> CreateMeetingResult {
+        if let Some(schedule) = &schedule {
+            schedule.validate()?;
+        }
+        save_meeting(ctx, id, expected_version, move |m: Meeting| -> UpdateMeetingResult {
+            let mut meeting = m.clone();
+            meeting.reaction_reset = schedule.clone();
+            Ok(meeting)
+        }).await
+    }
+    /// Replaces the standup notes of a member.
+    async fn set_member_notes(&self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "id of the meeting")] id: String,
+        #[graphql(desc = "version the change is based on, rejected with CONFLICT when outdated")] expected_version: Option<u64>,
+        #[graphql(desc = "id of member")] member_id: String,
+        notes: MemberNotes,
+    ) -> CreateMeetingResult {
+        notes.validate()?;
+        let notes = notes.trimmed();
+        save_meeting(ctx, id, expected_version, move |m: Meeting| -> UpdateMeetingResult {
+            let mut meeting = m.clone();
+            let member = meeting.members
+                .iter_mut()
+                .find(|m| m.id.as_str() == member_id)
+                .ok_or_else(|| MeetingError::NotFound(String::from("Invalid member id")))?;
+            member.notes = notes.clone();
+            Ok(meeting)
+        }).await
+    }
+    /// Opens an async standup: until `deadline` members submit their notes and reaction
+    /// instead of meeting live. It closes into a summary at the deadline.
+    async fn start_async_standup(&self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "id of the meeting")] id: String,
+        #[graphql(desc = "version the change is based on, rejected with CONFLICT when outdated")] expected_version: Option<u64>,
+        #[graphql(desc = "unix milliseconds, at most 7 days ahead")] deadline: u64,
+    ) -> CreateMeetingResult {
+        save_meeting(ctx, id, expected_version, move |m: Meeting| -> UpdateMeetingResult {
+            let mut meeting = m.clone();
+            if meeting.async_standup.is_some() {
+                return Err(MeetingError::ValidationFailed(String::from("An async standup is already open")));
+            }
+            meeting.async_standup = Some(AsyncStandup::new(deadline, now_millis())?);
+            Ok(meeting)
+        }).await
+    }
+    /// Sets the notes and reaction of a member and marks them as submitted in the open async standup.
+    async fn submit_async_update(&self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "id of the meeting")] id: String,
+        #[graphql(desc = "version the change is based on, rejected with CONFLICT when outdated")] expected_version: Option<u64>,
+        #[graphql(desc = "id of member")] member_id: String,
+        notes: MemberNotes,
+        #[graphql(desc = "key of one of the reactions of the meeting, or `NONE`")] reaction: String,
+    ) -> CreateMeetingResult {
+        notes.validate()?;
+        let notes = notes.trimmed();
+        save_meeting(ctx, id, expected_version, move |m: Meeting| -> UpdateMeetingResult {
+            let mut meeting = m.clone();
+            meeting.submit_async_update(&member_id, notes.clone(), reaction.clone(), now_millis())?;
+            Ok(meeting)
+        }).await
+    }
+    /// Closes the open async standup before its deadline.
+    async fn close_async_standup(&self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "id of the meeting")] id: String,
+        #[graphql(desc = "version the change is based on, rejected with CONFLICT when outdated")] expected_version: Option<u64>,
+    ) -> CreateMeetingResult {
+        save_meeting(ctx, id, expected_version, move |m: Meeting| -> UpdateMeetingResult {
+            let mut meeting = m.clone();
+            meeting.close_async_standup(now_millis())?;
+            Ok(meeting)
+        }).await
+    }
+    /// Marks a member as present or away. Away members keep their place on the roster
+    /// but move to the end, and are skipped when picking leaders and speakers.
+    async fn set_attendance(&self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "id of the meeting")] id: String,
+        #[graphql(desc = "version the change is based on, rejected with CONFLICT when outdated")] expected_version: Option<u64>,
+        #[graphql(desc = "id of member")] member_id: String,
+        attendance: Attendance,
+    ) -> CreateMeetingResult {
+        save_meeting(ctx, id, expected_version, move |m: Meeting| -> UpdateMeetingResult {
+            let mut meeting = m.clone();
+            let member = meeting.members
+                .iter_mut()
+                .find(|m| m.id.as_str() == member_id)
+                .ok_or_else(|| MeetingError::NotFound(String::from("Invalid member id")))?;
+            if member.attendance == attendance {
+                return Ok(meeting);
+            }
+            member.attendance = attendance;
+            let next = meeting.member_after(Some(&member_id));
+            if attendance != Attendance::Present {
+                // Move them behind the members who are still here.
+                let index = meeting.members.iter().position(|m| m.id.as_str() == member_id).unwrap_or_default();
+                let member = meeting.members.remove(index);
+                meeting.members.push(member);
+            }
+            arrange(&mut meeting.members);
+            if attendance != Attendance::Present && meeting.current_speaker_id.as_deref() == Some(member_id.as_str()) {
+                meeting.hand_over(next, now_millis());
+            }
+            Ok(meeting)
+        }).await
+    }
     async fn remove_member(&self,
         ctx: &Context<'_>,
         #[graphql(desc = "id of the meeting")] id: String,
+        #[graphql(desc = "version the change is based on, rejected with CONFLICT when outdated")] expected_version: Option<u64>,
         #[graphql(desc = "id of member")] member_id: String,
     ) -> CreateMeetingResult {
-        save_meeting(ctx, id, move |m| {
+        save_meeting(ctx, id, expected_version, move |m| {
             let mut meeting = m.clone();
+            let next = match meeting.current_speaker_id.as_deref() == Some(member_id.as_str()) {
+                true => Some(meeting.member_after(Some(&member_id))),
+                false => None,
+            };
             meeting.members = meeting
                 .members
                 .iter()
                 .filter(|m| m.id.to_string() != member_id)
                 .cloned()
                 .collect();
+            if let Some(next) = next {
+                meeting.hand_over(next, now_millis());
+            }
+            if let Some(poker) = meeting.poker.as_mut() {
+                poker.votes.retain(|vote| vote.member_id != member_id);
+            }
+            for item in meeting.parking_lot.iter_mut() {
+                if item.author_id.as_deref() == Some(member_id.as_str()) {
+                    item.author_id = None;
+                }
+                if item.owner_id.as_deref() == Some(member_id.as_str()) {
+                    item.owner_id = None;
+                }
+            }
             Ok(meeting)
         }).await
     }
     async fn shuffle_members(&self,
         ctx: &Context<'_>,
         #[graphql(desc = "id of the meeting")] id: String,
+        #[graphql(desc = "version the change is based on, rejected with CONFLICT when outdated")] expected_version: Option<u64>,
     ) -> CreateMeetingResult {
-        let save_memo = move |m: Meeting| {
+        let save_memo = move |m: Meeting| -> UpdateMeetingResult {
             let mut meeting = m.clone();
-            let mut rng = rand::thread_rng();
-            let mut member_list = meeting.members.to_vec();
-            member_list.shuffle(&mut rng);
-            meeting.members = member_list;
+            meeting.reorder(now_millis());
             Ok(meeting)
         };
-        save_meeting(ctx, id, save_memo).await
+        save_meeting(ctx, id, expected_version, save_memo).await
+    }
+    /// Orders members by `strategy`, which also becomes the meeting's default.
+    async fn order_members(&self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "id of the meeting")] id: String,
+        #[graphql(desc = "version the change is based on, rejected with CONFLICT when outdated")] expected_version: Option<u64>,
+        strategy: OrderStrategy,
+        #[graphql(desc = "seed of SEEDED_SHUFFLE, the current day when omitted")] seed: Option<u64>,
+    ) -> CreateMeetingResult {
+        save_meeting(ctx, id, expected_version, move |m: Meeting| -> UpdateMeetingResult {
+            let mut meeting = m.clone();
+            meeting.order_strategy = strategy;
+            meeting.order_seed = seed;
+            meeting.reorder(now_millis());
+            Ok(meeting)
+        }).await
+    }
+    /// Moves a member to `to_index` without reordering everyone else.
+    /// The member loses their pin, and members who are not present stay last.
+    async fn move_member(&self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "id of the meeting")] id: String,
+        #[graphql(desc = "version the change is based on, rejected with CONFLICT when outdated")] expected_version: Option<u64>,
+        #[graphql(desc = "id of member")] member_id: String,
+        #[graphql(desc = "position in the member list, starting at 0")] to_index: usize,
+    ) -> CreateMeetingResult {
+        save_meeting(ctx, id, expected_version, move |m: Meeting| -> UpdateMeetingResult {
+            let mut meeting = m.clone();
+            let from_index = meeting.members
+                .iter()
+                .position(|m| m.id.as_str() == member_id)
+                .ok_or_else(|| MeetingError::NotFound(String::from("Invalid member id")))?;
+            if to_index >= meeting.members.len() {
+                return Err(MeetingError::ValidationFailed(
+                    format!("Position must be below {}", meeting.members.len())
+                ));
+            }
+            let mut member = meeting.members.remove(from_index);
+            member.pin = MemberPin::None;
+            meeting.members.insert(to_index, member);
+            arrange(&mut meeting.members);
+            Ok(meeting)
+        }).await
+    }
+    /// Keeps a member first or last whatever the order strategy.
+    async fn pin_member(&self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "id of the meeting")] id: String,
+        #[graphql(desc = "version the change is based on, rejected with CONFLICT when outdated")] expected_version: Option<u64>,
+        #[graphql(desc = "id of member")] member_id: String,
+        pin: MemberPin,
+    ) -> CreateMeetingResult {
+        save_meeting(ctx, id, expected_version, move |m: Meeting| -> UpdateMeetingResult {
+            let mut meeting = m.clone();
+            let member = meeting.members
+                .iter_mut()
+                .find(|m| m.id.as_str() == member_id)
+                .ok_or_else(|| MeetingError::NotFound(String::from("Invalid member id")))?;
+            member.pin = pin;
+            arrange(&mut meeting.members);
+            Ok(meeting)
+        }).await
     }
     async fn new_leader(&self,
         ctx: &Context<'_>,
         #[graphql(desc = "id of the meeting")] id: String,
+        #[graphql(desc = "version the change is based on, rejected with CONFLICT when outdated")] expected_version: Option<u64>,
     ) -> CreateMeetingResult {
-        let save_memo = move |m: Meeting| {
+        let save_memo = move |m: Meeting| -> UpdateMeetingResult {
             let mut meeting = m.clone();
             let mut rng = rand::thread_rng();
-            let mut member_list = meeting.members.to_vec();
-            member_list.shuffle(&mut rng);
-            
-            let maybe_leader = member_list.get(0);
+            let maybe_leader = pick_leader(
+                meeting.leader_strategy,
+                &meeting.members,
+                meeting.leader_id.as_deref(),
+                &meeting.leader_history,
+                &mut rng,
+            );
             if let Some(leader) = maybe_leader {
-                meeting.leader_id = Some(leader.id.to_string());
+                append_capped(&mut meeting.leader_history, LeaderTerm {
+                    member_id: leader.clone(),
+                    chosen_at: now_millis(),
+                }, LEADER_HISTORY_LIMIT);
+                meeting.leader_id = Some(leader);
             }
             Ok(meeting)
         };
-        save_meeting(ctx, id, save_memo).await
+        save_meeting(ctx, id, expected_version, save_memo).await
+    }
+    async fn set_leader_strategy(&self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "id of the meeting")] id: String,
+        #[graphql(desc = "version the change is based on, rejected with CONFLICT when outdated")] expected_version: Option<u64>,
+        strategy: LeaderStrategy,
+    ) -> CreateMeetingResult {
+        save_meeting(ctx, id, expected_version, move |m: Meeting| -> UpdateMeetingResult {
+            let mut meeting = m.clone();
+            meeting.leader_strategy = strategy;
+            Ok(meeting)
+        }).await
     }
     async fn update_memo(&self,
         ctx: &Context<'_>,
         #[graphql(desc = "id of the meeting")] id: String,
+        #[graphql(desc = "version the change is based on, rejected with CONFLICT when outdated")] expected_version: Option<u64>,
         #[graphql(desc = "memo")] memo: String,
     ) -> CreateMeetingResult {
-        let save_memo = move |m: Meeting| {
+        let save_memo = move |m: Meeting| -> UpdateMeetingResult {
             let mut meeting = m.clone();
-            meeting.memo = memo.clone();
+            meeting.replace_memo(memo.clone())?;
             Ok(meeting)
         };
-        save_meeting(ctx, id, save_memo).await
+        save_meeting(ctx, id, expected_version, save_memo).await
+    }
+    /// Takes no `expectedVersion`: `baseRevision` tells which edits the change has not seen,
+    /// and those are merged in instead of rejecting the change.
+    async fn edit_memo(&self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "id of the meeting")] id: String,
+        #[graphql(desc = "memo revision the edit is based on, later edits are merged in")] base_revision: u64,
+        #[graphql(desc = "id the client picked for itself, echoed in memoEdits")] client_id: String,
+        ops: Vec<MemoOp>,
+    ) -> CreateMeetingResult {
+        save_meeting(ctx, id, None, move |m: Meeting| -> UpdateMeetingResult {
+            let mut meeting = m.clone();
+            meeting.edit_memo(base_revision, &client_id, &ops)?;
+            Ok(meeting)
+        }).await
+    }
+    async fn add_parking_item(&self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "id of the meeting")] id: String,
+        #[graphql(desc = "version the change is based on, rejected with CONFLICT when outdated")] expected_version: Option<u64>,
+        text: String,
+        #[graphql(desc = "id of the member raising the topic")] author_id: Option<String>,
+    ) -> CreateMeetingResult {
+        validate_text(&text)?;
+        save_meeting(ctx, id, expected_version, move |m: Meeting| -> UpdateMeetingResult {
+            let mut meeting = m.clone();
+            meeting.check_member(author_id.as_deref())?;
+            meeting.parking_lot.push(ParkingItem {
+                id: ID(uuid::Uuid::new_v4().to_string()),
+                text: text.clone(),
+                author_id: author_id.clone(),
+                owner_id: None,
+                created_at: now_millis(),
+                resolved: false,
+            });
+            Ok(meeting)
+        }).await
+    }
+    async fn edit_parking_item(&self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "id of the meeting")] id: String,
+        #[graphql(desc = "version the change is based on, rejected with CONFLICT when outdated")] expected_version: Option<u64>,
+        item_id: String,
+        text: String,
+    ) -> CreateMeetingResult {
+        validate_text(&text)?;
+        save_meeting(ctx, id, expected_version, move |m: Meeting| -> UpdateMeetingResult {
+            let mut meeting = m.clone();
+            meeting.parking_item_mut(&item_id)?.text = text.clone();
+            Ok(meeting)
+        }).await
+    }
+    async fn assign_parking_item(&self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "id of the meeting")] id: String,
+        #[graphql(desc = "version the change is based on, rejected with CONFLICT when outdated")] expected_version: Option<u64>,
+        item_id: String,
+        #[graphql(desc = "id of the member taking care of it, nobody when omitted")] owner_id: Option<String>,
+    ) -> CreateMeetingResult {
+        save_meeting(ctx, id, expected_version, move |m: Meeting| -> UpdateMeetingResult {
+            let mut meeting = m.clone();
+            meeting.check_member(owner_id.as_deref())?;
+            meeting.parking_item_mut(&item_id)?.owner_id = owner_id.clone();
+            Ok(meeting)
+        }).await
+    }
+    async fn resolve_parking_item(&self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "id of the meeting")] id: String,
+        #[graphql(desc = "version the change is based on, rejected with CONFLICT when outdated")] expected_version: Option<u64>,
+        item_id: String,
+        #[graphql(desc = "false to reopen the item", default = true)] resolved: bool,
+    ) -> CreateMeetingResult {
+        save_meeting(ctx, id, expected_version, move |m: Meeting| -> UpdateMeetingResult {
+            let mut meeting = m.clone();
+            meeting.parking_item_mut(&item_id)?.resolved = resolved;
+            Ok(meeting)
+        }).await
+    }
+    async fn delete_parking_item(&self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "id of the meeting")] id: String,
+        #[graphql(desc = "version the change is based on, rejected with CONFLICT when outdated")] expected_version: Option<u64>,
+        item_id: String,
+    ) -> CreateMeetingResult {
+        save_meeting(ctx, id, expected_version, move |m: Meeting| -> UpdateMeetingResult {
+            let mut meeting = m.clone();
+            meeting.parking_item_mut(&item_id)?;
+            meeting.parking_lot.retain(|item| item.id.as_str() != item_id);
+            Ok(meeting)
+        }).await
+    }
+    async fn start_poker_round(&self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "id of the meeting")] id: String,
+        #[graphql(desc = "version the change is based on, rejected with CONFLICT when outdated")] expected_version: Option<u64>,
+        #[graphql(desc = "what is being estimated")] topic: String,
+        #[graphql(desc = "secret picked by the client, required to reveal, re-vote or end the round")] facilitator_token: String,
+    ) -> CreateMeetingResult {
+        if topic.trim().is_empty() {
+            return Err(MeetingError::ValidationFailed(String::from("Poker topic must not be empty")).into());
+        }
+        if facilitator_token.is_empty() {
+            return Err(MeetingError::ValidationFailed(String::from("Facilitator token must not be empty")).into());
+        }
+        save_meeting(ctx, id, expected_version, move |m: Meeting| -> UpdateMeetingResult {
+            let mut meeting = m.clone();
+            // Replaces any running round, its votes are dropped unseen.
+            meeting.poker = Some(PokerRound::new(topic.clone(), facilitator_token.clone(), now_millis()));
+            Ok(meeting)
+        }).await
+    }
+    async fn cast_vote(&self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "id of the meeting")] id: String,
+        #[graphql(desc = "version the change is based on, rejected with CONFLICT when outdated")] expected_version: Option<u64>,
+        #[graphql(desc = "id of the voting member")] member_id: String,
+        #[graphql(desc = "key of a reaction worth story points")] reaction: String,
+    ) -> CreateMeetingResult {
+        save_meeting(ctx, id, expected_version, move |m: Meeting| -> UpdateMeetingResult {
+            let mut meeting = m.clone();
+            meeting.check_member(Some(&member_id))?;
+            let reaction = meeting.reaction(&reaction)?.clone();
+            meeting.poker_mut()?.vote(&member_id, &reaction)?;
+            Ok(meeting)
+        }).await
+    }
+    async fn reveal_votes(&self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "id of the meeting")] id: String,
+        #[graphql(desc = "version the change is based on, rejected with CONFLICT when outdated")] expected_version: Option<u64>,
+        facilitator_token: String,
+    ) -> CreateMeetingResult {
+        save_meeting(ctx, id, expected_version, move |m: Meeting| -> UpdateMeetingResult {
+            let mut meeting = m.clone();
+            let poker = meeting.poker_mut()?;
+            poker.check_facilitator(&facilitator_token)?;
+            poker.revealed = true;
+            Ok(meeting)
+        }).await
+    }
+    async fn revote(&self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "id of the meeting")] id: String,
+        #[graphql(desc = "version the change is based on, rejected with CONFLICT when outdated")] expected_version: Option<u64>,
+        facilitator_token: String,
+    ) -> CreateMeetingResult {
+        save_meeting(ctx, id, expected_version, move |m: Meeting| -> UpdateMeetingResult {
+            let mut meeting = m.clone();
+            let poker = meeting.poker_mut()?;
+            poker.check_facilitator(&facilitator_token)?;
+            poker.revealed = false;
+            poker.votes.clear();
+            Ok(meeting)
+        }).await
+    }
+    async fn end_poker_round(&self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "id of the meeting")] id: String,
+        #[graphql(desc = "version the change is based on, rejected with CONFLICT when outdated")] expected_version: Option<u64>,
+        facilitator_token: String,
+    ) -> CreateMeetingResult {
+        save_meeting(ctx, id, expected_version, move |m: Meeting| -> UpdateMeetingResult {
+            let mut meeting = m.clone();
+            meeting.poker_mut()?.check_facilitator(&facilitator_token)?;
+            meeting.poker = None;
+            Ok(meeting)
+        }).await
+    }
+    async fn set_speaker_time(&self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "id of the meeting")] id: String,
+        #[graphql(desc = "version the change is based on, rejected with CONFLICT when outdated")] expected_version: Option<u64>,
+        #[graphql(desc = "seconds each member may speak")] seconds: u64,
+    ) -> CreateMeetingResult {
+        if seconds == 0 || seconds > MAX_SPEAKER_SECS {
+            return Err(MeetingError::ValidationFailed(
+                format!("Speaker time must be between 1 and {} seconds", MAX_SPEAKER_SECS)
+            ).into());
+        }
+        save_meeting(ctx, id, expected_version, move |m: Meeting| -> UpdateMeetingResult {
+            let mut meeting = m.clone();
+            meeting.timer.set_duration(seconds);
+            Ok(meeting)
+        }).await
+    }
+    async fn start_timer(&self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "id of the meeting")] id: String,
+        #[graphql(desc = "version the change is based on, rejected with CONFLICT when outdated")] expected_version: Option<u64>,
+    ) -> CreateMeetingResult {
+        save_meeting(ctx, id, expected_version, move |m: Meeting| -> UpdateMeetingResult {
+            let mut meeting = m.clone();
+            let now = now_millis();
+            if meeting.current_speaker_id.is_none() {
+                let first = meeting.member_after(None);
+                meeting.hand_over(first, now);
+            }
+            if meeting.current_speaker_id.is_some() {
+                meeting.timer.start(now);
+            }
+            Ok(meeting)
+        }).await
+    }
+    async fn pause_timer(&self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "id of the meeting")] id: String,
+        #[graphql(desc = "version the change is based on, rejected with CONFLICT when outdated")] expected_version: Option<u64>,
+    ) -> CreateMeetingResult {
+        save_meeting(ctx, id, expected_version, move |m: Meeting| -> UpdateMeetingResult {
+            let mut meeting = m.clone();
+            meeting.timer.pause(now_millis());
+            Ok(meeting)
+        }).await
+    }
+    /// Passes the turn to the next member, or ends the round after the last one.
+    async fn next_speaker(&self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "id of the meeting")] id: String,
+        #[graphql(desc = "version the change is based on, rejected with CONFLICT when outdated")] expected_version: Option<u64>,
+    ) -> CreateMeetingResult {
+        save_meeting(ctx, id, expected_version, move |m: Meeting| -> UpdateMeetingResult {
+            let mut meeting = m.clone();
+            let next = meeting.member_after(meeting.current_speaker_id.as_deref());
+            meeting.hand_over(next, now_millis());
+            Ok(meeting)
+        }).await
+    }
+    /// Gives the turn back to the previous member.
+    async fn previous_speaker(&self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "id of the meeting")] id: String,
+        #[graphql(desc = "version the change is based on, rejected with CONFLICT when outdated")] expected_version: Option<u64>,
+    ) -> CreateMeetingResult {
+        save_meeting(ctx, id, expected_version, move |m: Meeting| -> UpdateMeetingResult {
+            let mut meeting = m.clone();
+            let previous = meeting.member_before(meeting.current_speaker_id.as_deref());
+            meeting.hand_over(previous, now_millis());
+            Ok(meeting)
+        }).await
+    }
+    async fn jump_to_speaker(&self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "id of the meeting")] id: String,
+        #[graphql(desc = "version the change is based on, rejected with CONFLICT when outdated")] expected_version: Option<u64>,
+        #[graphql(desc = "id of member")] member_id: String,
+    ) -> CreateMeetingResult {
+        save_meeting(ctx, id, expected_version, move |m: Meeting| -> UpdateMeetingResult {
+            let mut meeting = m.clone();
+            match meeting.members.iter().find(|m| m.id.as_str() == member_id) {
+                Some(member) if !member.is_present() => {
+                    return Err(MeetingError::ValidationFailed(String::from("Member is not present")));
+                },
+                Some(_) => {},
+                None => return Err(MeetingError::NotFound(String::from("Invalid member id"))),
+            }
+            meeting.hand_over(Some(member_id.clone()), now_millis());
+            Ok(meeting)
+        }).await
     }
 }
 
-
 pub struct SubscriptionRoot;
 #[Subscription]
 impl SubscriptionRoot {
-    async fn meeting(&self, ctx: &Context<'_>, #[graphql(desc = "Id of meeting")] id: String) -> impl Stream<Item = Result<Meeting, String>> {
-        let storage = ctx.data_unchecked::<Storage>().lock().await;
-
-        let client = storage.clone();
-        println!("start subscribe {:?}", &id);
+    /// Yields the stored meeting first, then every later version of it.
+    async fn meeting(&self, ctx: &Context<'_>, #[graphql(desc = "Id of meeting")] id: String) -> impl Stream<Item = Result<Meeting, Error>> {
+        let storage = ctx.data_unchecked::<Storage>().clone();
+        tracing::debug!("start subscribe {:?}", &id);
         async_stream::stream! {
-            let mut pubsub_conn = client.get_async_connection().await.unwrap().into_pubsub();
-            pubsub_conn.subscribe(&id).await.unwrap();
-            let mut pubsub_stream = pubsub_conn.on_message();
-            while let Some(next) = pubsub_stream.next().await {
-                let payload : String = next.get_payload().unwrap();
-                println!("channel meeting '{:?}' ", &id);
-                let meeting: Meeting = serde_json::from_str(&payload).unwrap();
+            // Subscribe before reading the snapshot so no update can fall in between.
+            let mut meetings = match storage.subscribe(&id).await {
+                Ok(meetings) => meetings,
+                Err(er) => {
+                    yield Err(MeetingError::from(er).into());
+                    return;
+                },
+            };
+            let mut version = match storage.get(&id).await {
+                Ok(Some(meeting)) => {
+                    let version = meeting.version;
+                    yield Ok(meeting);
+                    version
+                },
+                Ok(None) => {
+                    yield Err(MeetingError::NotFound(String::from("Invalid meeting id")).into());
+                    return;
+                },
+                Err(er) => {
+                    yield Err(MeetingError::from(er).into());
+                    return;
+                },
+            };
+            while let Some(meeting) = meetings.next().await {
+                if meeting.version <= version {
+                    continue;
+                }
+                version = meeting.version;
+                tracing::debug!("channel meeting {:?}", &id);
                 yield Ok(meeting);
             }
         }
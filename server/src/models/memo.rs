@@ -0,0 +1,323 @@
+use async_graphql::*;
+use serde:: { Serialize, Deserialize };
+use super::error::MeetingError;
+use super::history::append_capped;
+use super::meeting::Meeting;
+
+/// Memo edits remembered per meeting so clients can catch up; older ones are dropped.
+pub const MEMO_EDIT_LIMIT: usize = 50;
+
+/// One step of a memo edit, walking the memo from its start. Exactly one field is set.
+/// Lengths count Unicode scalar values.
+#[derive(Clone, PartialEq, SimpleObject, InputObject, Serialize, Deserialize)]
+#[graphql(input_name = "MemoOpInput")]
+pub struct MemoOp {
+    /// Keeps that many characters.
+    pub retain: Option<u64>,
+    /// Inserts the text.
+    pub insert: Option<String>,
+    /// Removes that many characters.
+    pub delete: Option<u64>,
+}
+
+/// Edit applied to the memo, as broadcast to the clients.
+#[derive(Clone, PartialEq, SimpleObject, Serialize, Deserialize)]
+pub struct MemoEdit {
+    /// Memo revision the edit produced.
+    pub revision: u64,
+    /// Id the editing client picked for itself, empty for `updateMemo`.
+    pub client_id: String,
+    pub ops: Vec<MemoOp>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum Op {
+    Retain(usize),
+    Insert(String),
+    Delete(usize),
+}
+
+pub type Operation = Vec<Op>;
+
+/// Appends `op`, merging it into the last step when both are of the same kind.
+fn push(ops: &mut Operation, op: Op) -> Result<(), MeetingError> {
+    match (ops.last_mut(), op) {
+        (_, Op::Retain(0)) | (_, Op::Delete(0)) => (),
+        (_, Op::Insert(text)) if text.is_empty() => (),
+        (Some(Op::Retain(last)), Op::Retain(n)) | (Some(Op::Delete(last)), Op::Delete(n)) => {
+            *last = last.checked_add(n).ok_or_else(too_long)?;
+        },
+        (Some(Op::Insert(last)), Op::Insert(text)) => last.push_str(&text),
+        (_, op) => ops.push(op),
+    }
+    Ok(())
+}
+
+fn too_long() -> MeetingError {
+    MeetingError::ValidationFailed(String::from("Memo edit is longer than the memo"))
+}
+
+/// Length of the memo the operation applies to, `None` when it does not fit a `usize`.
+fn base_len(ops: &[Op]) -> Option<usize> {
+    ops.iter().try_fold(0usize, |len, op| match op {
+        Op::Retain(n) | Op::Delete(n) => len.checked_add(*n),
+        Op::Insert(_) => Some(len),
+    })
+}
+
+/// Parses the steps of an edit, rejecting any retain or delete of more than `max_len` characters.
+pub fn parse_ops(ops: &[MemoOp], max_len: usize) -> Result<Operation, MeetingError> {
+    let count = |n: u64| match usize::try_from(n) {
+        Ok(n) if n <= max_len => Ok(n),
+        _ => Err(too_long()),
+    };
+    let mut operation = Vec::new();
+    for op in ops {
+        let op = match (op.retain, &op.insert, op.delete) {
+            (Some(n), None, None) => Op::Retain(count(n)?),
+            (None, Some(text), None) => Op::Insert(text.clone()),
+            (None, None, Some(n)) => Op::Delete(count(n)?),
+            _ => return Err(MeetingError::ValidationFailed(String::from("Memo op must set exactly one of retain, insert and delete"))),
+        };
+        push(&mut operation, op)?;
+    }
+    Ok(operation)
+}
+
+pub fn to_memo_ops(ops: &[Op]) -> Vec<MemoOp> {
+    ops.iter()
+        .map(|op| match op {
+            Op::Retain(n) => MemoOp { retain: Some(*n as u64), insert: None, delete: None },
+            Op::Insert(text) => MemoOp { retain: None, insert: Some(text.clone()), delete: None },
+            Op::Delete(n) => MemoOp { retain: None, insert: None, delete: Some(*n as u64) },
+        })
+        .collect()
+}
+
+/// Applies `ops` to `text`, which must span exactly the characters the operation walks over.
+pub fn apply(text: &str, ops: &[Op]) -> Result<String, MeetingError> {
+    let mut chars = text.chars();
+    if base_len(ops) != Some(text.chars().count()) {
+        return Err(MeetingError::ValidationFailed(String::from("Memo edit does not match the memo length")));
+    }
+    let mut result = String::with_capacity(text.len());
+    for op in ops {
+        match op {
+            Op::Retain(n) => result.extend(chars.by_ref().take(*n)),
+            Op::Insert(text) => result.push_str(text),
+            Op::Delete(n) => { chars.by_ref().take(*n).for_each(drop); },
+        }
+    }
+    Ok(result)
+}
+
+/// Rewrites two concurrent operations on the same memo so that applying `a` then `b'`
+/// gives the same memo as applying `b` then `a'`. Returns `(a', b')`.
+/// When both insert at the same place, the text of `a` goes first.
+pub fn transform(a: &[Op], b: &[Op]) -> Result<(Operation, Operation), MeetingError> {
+    if base_len(a).is_none() || base_len(a) != base_len(b) {
+        return Err(MeetingError::ValidationFailed(String::from("Memo edit does not match the memo length")));
+    }
+    let (mut a_prime, mut b_prime) = (Vec::new(), Vec::new());
+    let (mut a_ops, mut b_ops) = (a.iter().cloned(), b.iter().cloned());
+    let (mut op_a, mut op_b) = (a_ops.next(), b_ops.next());
+    loop {
+        match (op_a.take(), op_b.take()) {
+            (None, None) => break,
+            (Some(Op::Insert(text)), other) => {
+                push(&mut b_prime, Op::Retain(text.chars().count()))?;
+                push(&mut a_prime, Op::Insert(text))?;
+                op_a = a_ops.next();
+                op_b = other;
+            },
+            (other, Some(Op::Insert(text))) => {
+                push(&mut a_prime, Op::Retain(text.chars().count()))?;
+                push(&mut b_prime, Op::Insert(text))?;
+                op_a = other;
+                op_b = b_ops.next();
+            },
+            (Some(left), Some(right)) => {
+                let (n, m) = match (&left, &right) {
+                    (Op::Retain(n) | Op::Delete(n), Op::Retain(m) | Op::Delete(m)) => (*n, *m),
+                    _ => unreachable!("inserts are handled above"),
+                };
+                let len = n.min(m);
+                match (&left, &right) {
+                    (Op::Retain(_), Op::Retain(_)) => {
+                        push(&mut a_prime, Op::Retain(len))?;
+                        push(&mut b_prime, Op::Retain(len))?;
+                    },
+                    (Op::Delete(_), Op::Retain(_)) => push(&mut a_prime, Op::Delete(len))?,
+                    (Op::Retain(_), Op::Delete(_)) => push(&mut b_prime, Op::Delete(len))?,
+                    // Both removed the same characters, nothing left to do.
+                    _ => (),
+                }
+                op_a = shorten(left, len).or_else(|| a_ops.next());
+                op_b = shorten(right, len).or_else(|| b_ops.next());
+            },
+            // The lengths were checked above, so both run out together.
+            _ => unreachable!("operations of the same length"),
+        }
+    }
+    Ok((a_prime, b_prime))
+}
+
+/// What is left of a retain or delete once `len` characters were handled.
+fn shorten(op: Op, len: usize) -> Option<Op> {
+    match op {
+        Op::Retain(n) if n > len => Some(Op::Retain(n - len)),
+        Op::Delete(n) if n > len => Some(Op::Delete(n - len)),
+        _ => None,
+    }
+}
+
+impl Meeting {
+    /// Applies an edit based on `base_revision`, first transforming it over the edits made since.
+    pub fn edit_memo(&mut self, base_revision: u64, client_id: &str, ops: &[MemoOp]) -> Result<(), MeetingError> {
+        if base_revision > self.memo_revision {
+            return Err(MeetingError::ValidationFailed(String::from("Unknown memo revision")));
+        }
+        let missed = (self.memo_revision - base_revision) as usize;
+        if missed > self.memo_edits.len() {
+            // The edits it would need to be transformed over are gone, the client has to reload.
+            return Err(MeetingError::Conflict { expected: base_revision, actual: self.memo_revision });
+        }
+        let missed = &self.memo_edits[self.memo_edits.len() - missed..];
+        // The memo the edit is based on was at most as long as today's plus what was deleted since.
+        let deleted = missed
+            .iter()
+            .flat_map(|edit| &edit.ops)
+            .filter_map(|op| op.delete)
+            .try_fold(0usize, |len, n| len.checked_add(usize::try_from(n).ok()?))
+            .ok_or_else(too_long)?;
+        let max_len = self.memo.chars().count().checked_add(deleted).ok_or_else(too_long)?;
+        let mut ops = parse_ops(ops, max_len)?;
+        for edit in missed {
+            // Recorded edits were checked when they were made.
+            ops = transform(&ops, &parse_ops(&edit.ops, usize::MAX)?)?.0;
+        }
+        self.memo = apply(&self.memo, &ops)?;
+        self.record_memo_edit(client_id, &ops);
+        Ok(())
+    }
+
+    /// Replaces the whole memo, as an edit so that clients merging edits stay in sync.
+    pub fn replace_memo(&mut self, memo: String) -> Result<(), MeetingError> {
+        let mut ops = Vec::new();
+        push(&mut ops, Op::Delete(self.memo.chars().count()))?;
+        push(&mut ops, Op::Insert(memo.clone()))?;
+        self.memo = memo;
+        self.record_memo_edit("", &ops);
+        Ok(())
+    }
+
+    fn record_memo_edit(&mut self, client_id: &str, ops: &[Op]) {
+        self.memo_revision += 1;
+        append_capped(&mut self.memo_edits, MemoEdit {
+            revision: self.memo_revision,
+            client_id: client_id.to_string(),
+            ops: to_memo_ops(ops),
+        }, MEMO_EDIT_LIMIT);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meeting(memo: &str) -> Meeting {
+        serde_json::from_value(serde_json::json!({ "id": "meeting", "members": [], "memo": memo })).unwrap()
+    }
+
+    /// Applies `a` and `b` in both orders and returns the memo both orders lead to.
+    fn converge(text: &str, a: &[Op], b: &[Op]) -> String {
+        let (a_prime, b_prime) = transform(a, b).unwrap();
+        let a_then_b = apply(&apply(text, a).unwrap(), &b_prime).unwrap();
+        let b_then_a = apply(&apply(text, b).unwrap(), &a_prime).unwrap();
+        assert_eq!(a_then_b, b_then_a);
+        a_then_b
+    }
+
+    fn operation(steps: Vec<Op>) -> Operation {
+        let mut ops = Vec::new();
+        for op in steps {
+            push(&mut ops, op).unwrap();
+        }
+        ops
+    }
+
+    fn insert(at: usize, text: &str, len: usize) -> Operation {
+        operation(vec![Op::Retain(at), Op::Insert(text.to_string()), Op::Retain(len - at)])
+    }
+
+    fn delete(at: usize, n: usize, len: usize) -> Operation {
+        operation(vec![Op::Retain(at), Op::Delete(n), Op::Retain(len - at - n)])
+    }
+
+    #[test]
+    fn inserts_at_the_same_place_put_the_first_operation_first() {
+        assert_eq!(converge("ac", &insert(1, "x", 2), &insert(1, "y", 2)), "axyc");
+        assert_eq!(converge("ac", &insert(1, "y", 2), &insert(1, "x", 2)), "ayxc");
+    }
+
+    #[test]
+    fn insert_and_delete_converge() {
+        assert_eq!(converge("abcdef", &insert(3, "x", 6), &delete(1, 4, 6)), "axf");
+        assert_eq!(converge("abcdef", &delete(1, 4, 6), &insert(3, "x", 6)), "axf");
+        assert_eq!(converge("abcdef", &insert(6, "x", 6), &delete(2, 2, 6)), "abefx");
+    }
+
+    #[test]
+    fn overlapping_deletes_remove_each_character_once() {
+        assert_eq!(converge("abcdef", &delete(1, 3, 6), &delete(2, 3, 6)), "af");
+        assert_eq!(converge("abcdef", &delete(0, 6, 6), &delete(2, 2, 6)), "");
+    }
+
+    #[test]
+    fn edit_based_on_an_old_revision_is_merged() {
+        let mut meeting = meeting("hello");
+        meeting.edit_memo(0, "a", &to_memo_ops(&insert(0, "oh ", 5))).unwrap();
+        meeting.edit_memo(0, "b", &to_memo_ops(&insert(5, "!", 5))).unwrap();
+        assert_eq!(meeting.memo, "oh hello!");
+        assert_eq!(meeting.memo_revision, 2);
+    }
+
+    #[test]
+    fn edit_missing_more_than_the_remembered_edits_conflicts() {
+        let mut meeting = meeting("");
+        for revision in 0..=MEMO_EDIT_LIMIT {
+            meeting.edit_memo(revision as u64, "a", &to_memo_ops(&insert(revision, "x", revision))).unwrap();
+        }
+        let edit = to_memo_ops(&insert(0, "y", 0));
+        assert!(matches!(
+            meeting.edit_memo(0, "b", &edit),
+            Err(MeetingError::Conflict { expected: 0, actual }) if actual == MEMO_EDIT_LIMIT as u64 + 1
+        ));
+        // One edit newer is still remembered.
+        meeting.edit_memo(1, "b", &to_memo_ops(&insert(0, "y", 1))).unwrap();
+        assert!(meeting.memo.starts_with('y'));
+    }
+
+    #[test]
+    fn edit_of_another_length_is_rejected() {
+        let mut meeting = meeting("abc");
+        let short = to_memo_ops(&[Op::Retain(2), Op::Insert(String::from("x"))]);
+        assert!(matches!(meeting.edit_memo(0, "a", &short), Err(MeetingError::ValidationFailed(_))));
+        let long = to_memo_ops(&[Op::Retain(4)]);
+        assert!(matches!(meeting.edit_memo(0, "a", &long), Err(MeetingError::ValidationFailed(_))));
+        assert!(transform(&[Op::Retain(3)], &[Op::Retain(2)]).is_err());
+        assert_eq!(meeting.memo, "abc");
+        assert_eq!(meeting.memo_revision, 0);
+    }
+
+    #[test]
+    fn counts_beyond_the_memo_or_overflowing_are_rejected() {
+        let retain = [MemoOp { retain: Some(4), insert: None, delete: None }];
+        assert!(parse_ops(&retain, 3).is_err());
+        assert!(parse_ops(&retain, 4).is_ok());
+        let ops = vec![Op::Retain(usize::MAX), Op::Delete(1), Op::Retain(usize::MAX)];
+        assert!(apply("a", &ops).is_err());
+        let mut merged = vec![Op::Retain(usize::MAX)];
+        assert!(push(&mut merged, Op::Retain(1)).is_err());
+    }
+}
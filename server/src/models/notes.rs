@@ -0,0 +1,45 @@
+use async_graphql::*;
+use serde:: { Serialize, Deserialize };
+use super::error::MeetingError;
+
+const MAX_NOTE_CHARS: usize = 2000;
+
+/// Standup update of a member, filled in before or during the call.
+#[derive(Clone, Default, PartialEq, SimpleObject, InputObject, Serialize, Deserialize)]
+#[graphql(input_name = "MemberNotesInput")]
+#[serde(default)]
+pub struct MemberNotes {
+    /// What the member did since the last standup.
+    #[graphql(default)]
+    pub yesterday: String,
+    /// What the member plans to do next.
+    #[graphql(default)]
+    pub today: String,
+    /// What keeps the member from making progress.
+    #[graphql(default)]
+    pub blockers: String,
+}
+
+impl MemberNotes {
+    pub fn validate(&self) -> Result<(), MeetingError> {
+        for note in [&self.yesterday, &self.today, &self.blockers] {
+            if note.chars().count() > MAX_NOTE_CHARS {
+                return Err(MeetingError::ValidationFailed(format!("Notes must have at most {} characters each", MAX_NOTE_CHARS)));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        [&self.yesterday, &self.today, &self.blockers].iter().all(|note| note.trim().is_empty())
+    }
+
+    /// Notes without surrounding whitespace.
+    pub fn trimmed(&self) -> MemberNotes {
+        MemberNotes {
+            yesterday: self.yesterday.trim().to_string(),
+            today: self.today.trim().to_string(),
+            blockers: self.blockers.trim().to_string(),
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use async_graphql::*;
+use rand::{prelude::SliceRandom, rngs::StdRng, SeedableRng};
+use serde:: { Serialize, Deserialize };
+use super::meeting::{Meeting, Member};
+
+const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+/// How `orderMembers` and `shuffleMembers` put members in speaking order.
+#[derive(Enum, Copy, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum OrderStrategy {
+    /// Uniform random order.
+    #[default]
+    Shuffle,
+    /// Random order fixed by a seed, the current UTC day unless the meeting sets one.
+    SeededShuffle,
+    Alphabetical,
+    /// The last order recorded before today (UTC), backwards.
+    ReverseYesterday,
+    /// Uniform random order with the leader speaking last.
+    LeaderLast,
+}
+
+/// Keeps a member at one end of the order whatever the strategy.
+#[derive(Enum, Copy, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum MemberPin {
+    #[default]
+    None,
+    First,
+    Last,
+}
+
+/// Members of `meeting` in the order given by `strategy`.
+/// Pinned members go to their end and members who are not present go last.
+pub fn order_members(meeting: &Meeting, strategy: OrderStrategy, seed: Option<u64>, now: u64) -> Vec<Member> {
+    let mut members = meeting.members.to_vec();
+    match strategy {
+        OrderStrategy::Shuffle => members.shuffle(&mut rand::thread_rng()),
+        OrderStrategy::SeededShuffle => {
+            // Start from a fixed order so the seed alone decides the result.
+            members.sort_by(|a, b| a.id.as_str().cmp(b.id.as_str()));
+            let mut rng = StdRng::seed_from_u64(seed.unwrap_or(now / DAY_MS));
+            members.shuffle(&mut rng);
+        },
+        OrderStrategy::Alphabetical => {
+            members.sort_by_key(|m| m.name.to_lowercase());
+        },
+        OrderStrategy::ReverseYesterday => {
+            let today = now - now % DAY_MS;
+            let yesterday = meeting.order_history
+                .iter()
+                .rev()
+                .find(|order| order.recorded_at < today);
+            if let Some(order) = yesterday {
+                // Members who joined since then keep their place behind the others.
+                let position = |m: &Member| order.member_ids
+                    .iter()
+                    .rev()
+                    .position(|id| id == m.id.as_str())
+                    .unwrap_or(order.member_ids.len());
+                members.sort_by_key(position);
+            }
+        },
+        OrderStrategy::LeaderLast => {
+            members.shuffle(&mut rand::thread_rng());
+            let leader = meeting.leader_id.as_deref();
+            members.sort_by_key(|m| Some(m.id.as_str()) == leader);
+        },
+    }
+    arrange(&mut members);
+    members
+}
+
+/// Moves pinned members to their end and members who are not present behind everyone,
+/// keeping the order otherwise.
+pub fn arrange(members: &mut [Member]) {
+    members.sort_by_key(|m| match m.pin {
+        MemberPin::First => 0,
+        MemberPin::None => 1,
+        MemberPin::Last => 2,
+    });
+    members.sort_by_key(|m| !m.is_present());
+}
@@ -0,0 +1,44 @@
+use async_graphql::*;
+use serde:: { Serialize, Deserialize };
+use super::error::MeetingError;
+use super::meeting::Meeting;
+
+/// Topic put aside during the standup, to be discussed afterwards.
+#[derive(Clone, PartialEq, SimpleObject, Serialize, Deserialize)]
+pub struct ParkingItem {
+    pub id: ID,
+    pub text: String,
+    /// Member who raised the topic.
+    pub author_id: Option<String>,
+    /// Member who takes care of the topic.
+    pub owner_id: Option<String>,
+    /// Unix milliseconds when the item was added.
+    pub created_at: u64,
+    pub resolved: bool,
+}
+
+pub fn validate_text(text: &str) -> Result<(), MeetingError> {
+    if text.trim().is_empty() {
+        return Err(MeetingError::ValidationFailed(String::from("Parking lot item must not be empty")));
+    }
+    Ok(())
+}
+
+impl Meeting {
+    pub fn parking_item_mut(&mut self, item_id: &str) -> Result<&mut ParkingItem, MeetingError> {
+        self.parking_lot
+            .iter_mut()
+            .find(|item| item.id.as_str() == item_id)
+            .ok_or_else(|| MeetingError::NotFound(String::from("Invalid parking lot item id")))
+    }
+
+    /// Fails unless `member_id` is empty or one of the members.
+    pub fn check_member(&self, member_id: Option<&str>) -> Result<(), MeetingError> {
+        match member_id {
+            Some(member_id) if !self.members.iter().any(|m| m.id.as_str() == member_id) => {
+                Err(MeetingError::NotFound(String::from("Invalid member id")))
+            },
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use async_graphql::*;
+use serde:: { Serialize, Deserialize };
+use super::error::MeetingError;
+use super::meeting::Meeting;
+use super::reaction::Reaction;
+
+#[derive(Clone, PartialEq, SimpleObject, Serialize, Deserialize)]
+pub struct PokerVote {
+    pub member_id: String,
+    /// Key of the reaction voted with.
+    pub reaction: String,
+    /// Story points of the reaction when the vote was cast.
+    pub points: u32,
+}
+
+/// Estimation round. Votes are kept secret from every client until the facilitator reveals them.
+#[derive(Clone, PartialEq, SimpleObject, Serialize, Deserialize)]
+#[graphql(complex)]
+pub struct PokerRound {
+    pub id: ID,
+    pub topic: String,
+    /// Unix milliseconds when the round was started.
+    pub started_at: u64,
+    pub revealed: bool,
+    /// Secret of the client that started the round, needed to reveal, re-vote or end it.
+    #[graphql(skip)]
+    pub facilitator_token: String,
+    #[graphql(skip)]
+    pub votes: Vec<PokerVote>,
+}
+
+#[derive(SimpleObject)]
+pub struct PokerSummary {
+    pub min: u32,
+    pub max: u32,
+    pub mean: f64,
+    pub median: f64,
+    /// Everybody voted the same.
+    pub consensus: bool,
+}
+
+#[ComplexObject]
+impl PokerRound {
+    /// Members who have voted, oldest vote first.
+    async fn voter_ids(&self) -> Vec<String> {
+        self.votes.iter().map(|vote| vote.member_id.clone()).collect()
+    }
+
+    /// Votes, `None` until revealed.
+    async fn votes(&self) -> Option<Vec<PokerVote>> {
+        self.revealed.then(|| self.votes.clone())
+    }
+
+    /// `None` until revealed, or when nobody voted.
+    async fn summary(&self) -> Option<PokerSummary> {
+        match self.revealed {
+            true => self.tally(),
+            false => None,
+        }
+    }
+}
+
+impl PokerRound {
+    pub fn new(topic: String, facilitator_token: String, now: u64) -> PokerRound {
+        PokerRound {
+            id: ID(uuid::Uuid::new_v4().to_string()),
+            topic,
+            started_at: now,
+            revealed: false,
+            facilitator_token,
+            votes: Vec::new(),
+        }
+    }
+
+    fn tally(&self) -> Option<PokerSummary> {
+        let mut points: Vec<u32> = self.votes.iter().map(|vote| vote.points).collect();
+        points.sort_unstable();
+        let (min, max) = (*points.first()?, *points.last()?);
+        let middle = points.len() / 2;
+        let median = match points.len() % 2 {
+            0 => (points[middle - 1] as f64 + points[middle] as f64) / 2.0,
+            _ => points[middle] as f64,
+        };
+        Some(PokerSummary {
+            min,
+            max,
+            mean: points.iter().map(|p| *p as u64).sum::<u64>() as f64 / points.len() as f64,
+            median,
+            consensus: min == max,
+        })
+    }
+
+    /// Records the vote of `member_id`, replacing an earlier one.
+    pub fn vote(&mut self, member_id: &str, reaction: &Reaction) -> Result<(), MeetingError> {
+        let points = match reaction.points {
+            Some(points) => points,
+            None => return Err(MeetingError::ValidationFailed(String::from("Vote with a reaction worth story points"))),
+        };
+        if self.revealed {
+            return Err(MeetingError::ValidationFailed(String::from("Votes are revealed, start a re-vote first")));
+        }
+        self.votes.retain(|vote| vote.member_id != member_id);
+        self.votes.push(PokerVote {
+            member_id: member_id.to_string(),
+            reaction: reaction.key.clone(),
+            points,
+        });
+        Ok(())
+    }
+
+    pub fn check_facilitator(&self, facilitator_token: &str) -> Result<(), MeetingError> {
+        if self.facilitator_token != facilitator_token {
+            return Err(MeetingError::Forbidden(String::from("Only the facilitator of the round can do this")));
+        }
+        Ok(())
+    }
+}
+
+impl Meeting {
+    pub fn poker_mut(&mut self) -> Result<&mut PokerRound, MeetingError> {
+        self.poker
+            .as_mut()
+            .ok_or_else(|| MeetingError::NotFound(String::from("No poker round is running")))
+    }
+}
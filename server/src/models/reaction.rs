@@ -0,0 +1,128 @@
+use async_graphql::*;
+use serde:: { Serialize, Deserialize };
+use super::error::MeetingError;
+use super::meeting::Meeting;
+
+/// Key of the reaction members start with. Always accepted, whatever the meeting defines.
+pub const NO_REACTION: &str = "NONE";
+/// Most reactions a meeting may define.
+pub const MAX_REACTIONS: usize = 32;
+const MAX_SYMBOL_CHARS: usize = 16;
+const MAX_LABEL_CHARS: usize = 32;
+/// Most story points a reaction may be worth.
+pub const MAX_POINTS: u32 = 1000;
+
+/// One entry of the reaction palette of a meeting.
+#[derive(Clone, PartialEq, SimpleObject, InputObject, Serialize, Deserialize)]
+#[graphql(input_name = "ReactionInput")]
+pub struct Reaction {
+    /// Stable id members refer to, unique within the meeting.
+    pub key: String,
+    /// Emoji or short text shown on the card, e.g. "🤒" or "XL".
+    pub symbol: String,
+    /// What the reaction means, e.g. "sick". May be empty.
+    #[serde(default)]
+    #[graphql(default)]
+    pub label: String,
+    /// Story points of the reaction, only reactions with points count as planning poker votes.
+    #[serde(default)]
+    pub points: Option<u32>,
+}
+
+fn reaction(key: &str, symbol: &str, label: &str, points: Option<u32>) -> Reaction {
+    Reaction {
+        key: key.to_string(),
+        symbol: symbol.to_string(),
+        label: label.to_string(),
+        points,
+    }
+}
+
+/// Palette of meetings that have not defined their own.
+/// The keys are the names of the reactions before meetings could define them, so stored members keep theirs.
+pub fn default_reactions() -> Vec<Reaction> {
+    vec![
+        reaction("THUMBSUP", "\u{1F44D}", "good", None),
+        reaction("THUMBSDOWN", "\u{1F44E}", "bad", None),
+        reaction("SMILE", "\u{1F642}", "happy", None),
+        reaction("CLAP", "\u{1F44F}", "well done", None),
+        reaction("ZERO", "0\u{fe0f}\u{20e3}", "", Some(0)),
+        reaction("I", "1\u{fe0f}\u{20e3}", "", Some(1)),
+        reaction("II", "2\u{fe0f}\u{20e3}", "", Some(2)),
+        reaction("III", "3\u{fe0f}\u{20e3}", "", Some(3)),
+        reaction("IV", "4\u{fe0f}\u{20e3}", "", Some(4)),
+        reaction("V", "5\u{fe0f}\u{20e3}", "", Some(5)),
+        reaction("VI", "6\u{fe0f}\u{20e3}", "", Some(6)),
+        reaction("VII", "7\u{fe0f}\u{20e3}", "", Some(7)),
+        reaction("VIII", "8\u{fe0f}\u{20e3}", "", Some(8)),
+        reaction("IX", "9\u{fe0f}\u{20e3}", "", Some(9)),
+        reaction("X", "\u{1F51F}", "", Some(10)),
+    ]
+}
+
+pub fn validate_reactions(reactions: &[Reaction]) -> Result<(), MeetingError> {
+    let invalid = |msg: &str| Err(MeetingError::ValidationFailed(msg.to_string()));
+    if reactions.is_empty() || reactions.len() > MAX_REACTIONS {
+        return invalid(&format!("Define between 1 and {} reactions", MAX_REACTIONS));
+    }
+    for (i, reaction) in reactions.iter().enumerate() {
+        if reaction.key.is_empty() || reaction.key == NO_REACTION {
+            return invalid(&format!("Reaction key must not be empty or {}", NO_REACTION));
+        }
+        if reactions[..i].iter().any(|r| r.key == reaction.key) {
+            return invalid(&format!("Reaction key {} is used twice", reaction.key));
+        }
+        if reaction.symbol.trim().is_empty() || reaction.symbol.chars().count() > MAX_SYMBOL_CHARS {
+            return invalid(&format!("Reaction symbol must have 1 to {} characters", MAX_SYMBOL_CHARS));
+        }
+        if reaction.label.chars().count() > MAX_LABEL_CHARS {
+            return invalid(&format!("Reaction label must have at most {} characters", MAX_LABEL_CHARS));
+        }
+        if reaction.points.is_some_and(|points| points > MAX_POINTS) {
+            return invalid(&format!("Reaction points must be at most {}", MAX_POINTS));
+        }
+    }
+    Ok(())
+}
+
+impl Meeting {
+    pub fn reaction(&self, key: &str) -> Result<&Reaction, MeetingError> {
+        self.reactions
+            .iter()
+            .find(|r| r.key == key)
+            .ok_or_else(|| MeetingError::ValidationFailed(String::from("Reaction is not defined for this meeting")))
+    }
+
+    /// Fails unless `key` is one of the reactions of the meeting or `NONE`.
+    pub fn check_reaction(&self, key: &str) -> Result<(), MeetingError> {
+        match key == NO_REACTION {
+            true => Ok(()),
+            false => self.reaction(key).map(|_| ()),
+        }
+    }
+
+    /// Replaces the palette. Members using a reaction that is gone lose it,
+    /// as do poker votes whose reaction is gone or no longer worth points.
+    pub fn set_reactions(&mut self, reactions: Vec<Reaction>) {
+        self.reactions = reactions;
+        let keys: Vec<String> = self.reactions.iter().map(|r| r.key.clone()).collect();
+        for member in self.members.iter_mut() {
+            if !keys.contains(&member.reaction) {
+                member.reaction = NO_REACTION.to_string();
+                member.reaction_at = None;
+            }
+        }
+        if let Some(poker) = self.poker.as_mut() {
+            let reactions = &self.reactions;
+            poker.votes.retain_mut(|vote| {
+                match reactions.iter().find(|r| r.key == vote.reaction).and_then(|r| r.points) {
+                    Some(points) => {
+                        vote.points = points;
+                        true
+                    },
+                    None => false,
+                }
+            });
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use async_graphql::*;
+use chrono::{DateTime, NaiveTime, TimeDelta, TimeZone};
+use chrono_tz::Tz;
+use serde:: { Serialize, Deserialize };
+use crate::storage::Storage;
+use super::error::MeetingError;
+use super::meeting::{update_meeting, Meeting};
+use super::reaction::NO_REACTION;
+use super::timer::now_millis;
+
+/// Local time of day at which a meeting clears its reactions.
+#[derive(Clone, PartialEq, SimpleObject, InputObject, Serialize, Deserialize)]
+#[graphql(input_name = "ReactionResetInput")]
+pub struct ReactionReset {
+    /// IANA time zone name, e.g. "Europe/Berlin".
+    pub time_zone: String,
+    pub hour: u32,
+    pub minute: u32,
+}
+
+impl ReactionReset {
+    pub fn validate(&self) -> Result<(), MeetingError> {
+        if self.time_zone.parse::<Tz>().is_err() {
+            return Err(MeetingError::ValidationFailed(format!("Unknown time zone {}", self.time_zone)));
+        }
+        if self.hour > 23 || self.minute > 59 {
+            return Err(MeetingError::ValidationFailed(String::from("Reset time must be between 00:00 and 23:59")));
+        }
+        Ok(())
+    }
+
+    /// Unix milliseconds of the latest reset at or before `now`.
+    /// On days the clock skips the reset time, the reset happens an hour later.
+    pub fn latest_before(&self, now: u64) -> Option<u64> {
+        let tz: Tz = self.time_zone.parse().ok()?;
+        let time = NaiveTime::from_hms_opt(self.hour, self.minute, 0)?;
+        let now = DateTime::from_timestamp_millis(now as i64)?;
+        let mut day = now.with_timezone(&tz).date_naive();
+        loop {
+            let local = day.and_time(time);
+            let reset = tz
+                .from_local_datetime(&local)
+                .earliest()
+                .or_else(|| tz.from_local_datetime(&(local + TimeDelta::hours(1))).earliest())?;
+            if reset <= now {
+                return Some(reset.timestamp_millis() as u64);
+            }
+            day = day.pred_opt()?;
+        }
+    }
+}
+
+impl Meeting {
+    /// Clears the reactions picked before `cutoff` (unix milliseconds).
+    /// Reactions of unknown age count as older. Returns whether any was cleared.
+    pub fn clear_reactions_before(&mut self, cutoff: u64) -> bool {
+        let mut cleared = false;
+        for member in self.members.iter_mut() {
+            if member.reaction != NO_REACTION && member.reaction_at.is_none_or(|at| at < cutoff) {
+                member.reaction = NO_REACTION.to_string();
+                member.reaction_at = None;
+                cleared = true;
+            }
+        }
+        cleared
+    }
+}
+
+/// Clears the reactions of meeting `id` picked before its latest reset time.
+pub async fn reset_if_due(storage: &Storage, id: &str) -> Result<(), MeetingError> {
+    let cutoff = match storage.get(id).await? {
+        Some(meeting) => match meeting.reaction_reset.as_ref().and_then(|r| r.latest_before(now_millis())) {
+            // Saves only when there is something to clear, so idle meetings keep their version.
+            Some(cutoff) if meeting.clone().clear_reactions_before(cutoff) => cutoff,
+            _ => return Ok(()),
+        },
+        None => return Ok(()),
+    };
+    update_meeting(storage, id, None, |m: Meeting| {
+        let mut meeting = m.clone();
+        meeting.clear_reactions_before(cutoff);
+        Ok(meeting)
+    }).await?;
+    Ok(())
+}
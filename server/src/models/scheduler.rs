@@ -0,0 +1,40 @@
+use std::time::Duration;
+use crate::storage::Storage;
+use super::async_standup::close_if_due;
+use super::meeting::Meeting;
+use super::reaction_reset::reset_if_due;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+impl Meeting {
+    /// The meeting has work for the scheduler: a daily reaction reset or an async standup to close.
+    pub fn is_scheduled(&self) -> bool {
+        self.reaction_reset.is_some() || self.async_standup.is_some()
+    }
+}
+
+/// Clears reactions once their reset time has passed and closes async standups
+/// past their deadline, checking every minute.
+pub fn spawn_scheduler(storage: Storage) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            let ids = match storage.scheduled_ids().await {
+                Ok(ids) => ids,
+                Err(er) => {
+                    tracing::error!("failed to list scheduled meetings: {}", er);
+                    continue;
+                },
+            };
+            for id in ids {
+                if let Err(er) = reset_if_due(&storage, &id).await {
+                    tracing::error!("failed to reset reactions of {}: {}", id, er.message());
+                }
+                if let Err(er) = close_if_due(&storage, &id).await {
+                    tracing::error!("failed to close async standup of {}: {}", id, er.message());
+                }
+            }
+        }
+    });
+}
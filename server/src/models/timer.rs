@@ -0,0 +1,92 @@
+use async_graphql::*;
+use serde:: { Serialize, Deserialize };
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const DEFAULT_SPEAKER_SECS: u64 = 120;
+pub const MAX_SPEAKER_SECS: u64 = 60 * 60;
+
+pub fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Timebox of the member who is speaking, see `Meeting::current_speaker_id`.
+///
+/// Only changes are stored and broadcast; clients count down on their own
+/// from `remaining_ms`, which was the time left at `started_at`.
+#[derive(Clone, PartialEq, SimpleObject, Serialize, Deserialize)]
+#[graphql(complex)]
+#[serde(default)]
+pub struct SpeakerTimer {
+    /// Time each member may speak.
+    pub duration_secs: u64,
+    /// Unix milliseconds when the clock was started, `None` while paused.
+    pub started_at: Option<u64>,
+    /// Milliseconds left at `started_at`, or when the clock was paused.
+    /// Goes negative once the speaker runs over.
+    pub remaining_ms: i64,
+}
+
+impl Default for SpeakerTimer {
+    fn default() -> Self {
+        SpeakerTimer {
+            duration_secs: DEFAULT_SPEAKER_SECS,
+            started_at: None,
+            remaining_ms: (DEFAULT_SPEAKER_SECS * 1000) as i64,
+        }
+    }
+}
+
+#[ComplexObject]
+impl SpeakerTimer {
+    /// Server clock in unix milliseconds, so clients can correct for their own.
+    async fn server_time(&self) -> u64 {
+        now_millis()
+    }
+}
+
+impl SpeakerTimer {
+    pub fn is_running(&self) -> bool {
+        self.started_at.is_some()
+    }
+
+    pub fn remaining_at(&self, now: u64) -> i64 {
+        match self.started_at {
+            Some(started_at) => self.remaining_ms - now.saturating_sub(started_at) as i64,
+            None => self.remaining_ms,
+        }
+    }
+
+    pub fn set_duration(&mut self, secs: u64) {
+        self.duration_secs = secs;
+        if !self.is_running() {
+            self.remaining_ms = (secs * 1000) as i64;
+        }
+    }
+
+    /// Starts or resumes the clock.
+    pub fn start(&mut self, now: u64) {
+        if !self.is_running() {
+            self.started_at = Some(now);
+        }
+    }
+
+    pub fn pause(&mut self, now: u64) {
+        self.remaining_ms = self.remaining_at(now);
+        self.started_at = None;
+    }
+
+    /// Gives the next speaker a full timebox. A running clock keeps running.
+    pub fn reset(&mut self, now: u64) {
+        self.remaining_ms = (self.duration_secs * 1000) as i64;
+        self.started_at = self.started_at.map(|_| now);
+    }
+
+    /// Stops the clock with a full timebox, once nobody is speaking.
+    pub fn stop(&mut self) {
+        self.remaining_ms = (self.duration_secs * 1000) as i64;
+        self.started_at = None;
+    }
+}
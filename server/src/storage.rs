@@ -0,0 +1,154 @@
+pub mod memory;
+pub mod redis_hub;
+pub mod redis_store;
+pub mod sqlite;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use std::{collections::HashMap, fmt, sync::{Arc, Mutex}, time::{Duration, SystemTime, UNIX_EPOCH}};
+use tokio::sync::broadcast::{self, error::RecvError};
+use crate::models::meeting::Meeting;
+
+#[derive(Debug)]
+pub enum StoreError {
+    Unavailable(String),
+    Broken(String),
+}
+
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StoreError::Unavailable(detail) => write!(f, "Failed to connect storage: {}", detail),
+            StoreError::Broken(detail) => write!(f, "Meeting object is broken: {}", detail),
+        }
+    }
+}
+
+pub type StoreResult<T> = Result<T, StoreError>;
+
+/// Backend that keeps meetings and notifies watchers when one of them changes.
+#[async_trait]
+pub trait MeetingStore: Send + Sync {
+    async fn get(&self, id: &str) -> StoreResult<Option<Meeting>>;
+
+    /// Writes `new` only if the stored meeting still equals `current`.
+    /// `current: None` means the meeting must not exist yet.
+    /// Returns `false` when somebody else changed the meeting first.
+    async fn compare_and_set(&self, id: &str, current: Option<&Meeting>, new: &Meeting) -> StoreResult<bool>;
+
+    async fn publish(&self, id: &str, meeting: &Meeting) -> StoreResult<()>;
+
+    async fn subscribe(&self, id: &str) -> StoreResult<BoxStream<'static, Meeting>>;
+
+    /// Ids of the meetings with work for the scheduler, see `Meeting::is_scheduled`.
+    async fn scheduled_ids(&self) -> StoreResult<Vec<String>>;
+
+    /// Deletes meetings that have not been saved since `cutoff` and returns how many.
+    /// Backends that expire keys natively keep this default, which does nothing.
+    async fn remove_untouched(&self, _cutoff: SystemTime) -> StoreResult<usize> {
+        Ok(0)
+    }
+}
+
+pub type Storage = Arc<dyn MeetingStore>;
+
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Removes meetings that have not been saved within `retention`, once an hour.
+pub fn spawn_sweeper(storage: Storage, retention: Duration) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(SWEEP_INTERVAL);
+        loop {
+            interval.tick().await;
+            let cutoff = SystemTime::now().checked_sub(retention).unwrap_or(UNIX_EPOCH);
+            match storage.remove_untouched(cutoff).await {
+                Ok(0) => {},
+                Ok(count) => tracing::info!("removed {} expired meetings", count),
+                Err(er) => tracing::error!("failed to remove expired meetings: {}", er),
+            }
+        }
+    });
+}
+
+const CHANNEL_CAPACITY: usize = 16;
+
+/// In-process pub/sub used by backends that have no broker of their own.
+#[derive(Default)]
+pub struct Channels {
+    senders: Arc<Mutex<HashMap<String, broadcast::Sender<Meeting>>>>,
+}
+
+/// Receiver of one subscription; removes the channel when the last one is dropped.
+struct Listener {
+    id: String,
+    senders: Arc<Mutex<HashMap<String, broadcast::Sender<Meeting>>>>,
+    receiver: broadcast::Receiver<Meeting>,
+}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        let mut senders = self.senders.lock().unwrap();
+        // Our own receiver is still counted here.
+        if senders.get(&self.id).is_some_and(|sender| sender.receiver_count() <= 1) {
+            senders.remove(&self.id);
+        }
+    }
+}
+
+impl Channels {
+    pub fn publish(&self, id: &str, meeting: &Meeting) {
+        let senders = self.senders.lock().unwrap();
+        if let Some(sender) = senders.get(id) {
+            let _ = sender.send(meeting.clone());
+        }
+    }
+
+    pub fn subscribe(&self, id: &str) -> BoxStream<'static, Meeting> {
+        let receiver = self.senders
+            .lock()
+            .unwrap()
+            .entry(id.to_string())
+            .or_insert_with(|| broadcast::channel(CHANNEL_CAPACITY).0)
+            .subscribe();
+        let mut listener = Listener {
+            id: id.to_string(),
+            senders: Arc::clone(&self.senders),
+            receiver,
+        };
+        Box::pin(async_stream::stream! {
+            loop {
+                match listener.receiver.recv().await {
+                    Ok(meeting) => yield meeting,
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt as _;
+
+    fn meeting(version: u64) -> Meeting {
+        serde_json::from_value(serde_json::json!({ "id": "meeting", "version": version, "members": [], "memo": "" })).unwrap()
+    }
+
+    #[tokio::test]
+    async fn channel_is_removed_with_its_last_subscriber() {
+        let channels = Channels::default();
+        let mut first = channels.subscribe("meeting");
+        let second = channels.subscribe("meeting");
+        channels.publish("meeting", &meeting(1));
+        assert_eq!(first.next().await.map(|m| m.version), Some(1));
+
+        drop(second);
+        assert!(channels.senders.lock().unwrap().contains_key("meeting"));
+        drop(first);
+        assert!(channels.senders.lock().unwrap().is_empty());
+        // Publishing without subscribers does not bring it back.
+        channels.publish("meeting", &meeting(2));
+        assert!(channels.senders.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,68 @@
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use std::{collections::HashMap, sync::Mutex, time::SystemTime};
+use crate::models::meeting::Meeting;
+use super::{Channels, MeetingStore, StoreResult};
+
+/// Keeps meetings in process memory. Everything is lost on restart,
+/// which is what you want for local development and tests.
+#[derive(Default)]
+pub struct MemoryStore {
+    meetings: Mutex<HashMap<String, Entry>>,
+    channels: Channels,
+}
+
+struct Entry {
+    meeting: Meeting,
+    saved_at: SystemTime,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl MeetingStore for MemoryStore {
+    async fn get(&self, id: &str) -> StoreResult<Option<Meeting>> {
+        Ok(self.meetings.lock().unwrap().get(id).map(|entry| entry.meeting.clone()))
+    }
+
+    async fn compare_and_set(&self, id: &str, current: Option<&Meeting>, new: &Meeting) -> StoreResult<bool> {
+        let mut meetings = self.meetings.lock().unwrap();
+        if meetings.get(id).map(|entry| &entry.meeting) != current {
+            return Ok(false);
+        }
+        meetings.insert(id.to_string(), Entry {
+            meeting: new.clone(),
+            saved_at: SystemTime::now(),
+        });
+        Ok(true)
+    }
+
+    async fn publish(&self, id: &str, meeting: &Meeting) -> StoreResult<()> {
+        self.channels.publish(id, meeting);
+        Ok(())
+    }
+
+    async fn subscribe(&self, id: &str) -> StoreResult<BoxStream<'static, Meeting>> {
+        Ok(self.channels.subscribe(id))
+    }
+
+    async fn scheduled_ids(&self) -> StoreResult<Vec<String>> {
+        let meetings = self.meetings.lock().unwrap();
+        Ok(meetings
+            .iter()
+            .filter(|(_, entry)| entry.meeting.is_scheduled())
+            .map(|(id, _)| id.clone())
+            .collect())
+    }
+
+    async fn remove_untouched(&self, cutoff: SystemTime) -> StoreResult<usize> {
+        let mut meetings = self.meetings.lock().unwrap();
+        let before = meetings.len();
+        meetings.retain(|_, entry| entry.saved_at >= cutoff);
+        Ok(before - meetings.len())
+    }
+}
@@ -0,0 +1,183 @@
+use futures::stream::BoxStream;
+use futures::StreamExt as _;
+use redis::aio::{PubSubSink, PubSubStream};
+use std::{collections::HashMap, sync::Arc, time::Duration};
+use tokio::sync::{broadcast::{self, error::RecvError}, Mutex};
+use crate::models::meeting::Meeting;
+use super::{redis_store::parse, StoreError, StoreResult};
+
+const CHANNEL_CAPACITY: usize = 16;
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Shares one Redis pub/sub connection between every watcher in the process.
+///
+/// Each meeting channel is subscribed on Redis once, when its first watcher
+/// arrives, and unsubscribed when its last watcher leaves. Messages are fanned
+/// out to local `broadcast` receivers. If the connection drops, the hub keeps
+/// reconnecting and subscribes again to every channel that is still watched.
+pub struct SubscriptionHub {
+    client: redis::Client,
+    hub: Arc<Hub>,
+}
+
+#[derive(Default)]
+struct Hub {
+    /// Watched channels. Never locked across an await, so Redis round trips
+    /// do not hold up fanning out messages or other watchers.
+    channels: std::sync::Mutex<HashMap<String, Channel>>,
+    /// Locked while talking to Redis, which keeps subscribes and unsubscribes in order.
+    sink: Mutex<Option<PubSubSink>>,
+}
+
+struct Channel {
+    sender: broadcast::Sender<Meeting>,
+    watchers: usize,
+}
+
+/// Held by every subscription stream; leaves the channel when dropped.
+struct Watch {
+    id: String,
+    hub: Arc<Hub>,
+}
+
+impl Drop for Watch {
+    fn drop(&mut self) {
+        let id = std::mem::take(&mut self.id);
+        let hub = Arc::clone(&self.hub);
+        tokio::spawn(async move { hub.leave(&id).await });
+    }
+}
+
+fn unavailable(er: redis::RedisError) -> StoreError {
+    StoreError::Unavailable(er.to_string())
+}
+
+impl Hub {
+    fn is_watched(&self, id: &str) -> bool {
+        self.channels.lock().unwrap().contains_key(id)
+    }
+
+    /// Subscribes or unsubscribes `id` on Redis, depending on whether it is watched
+    /// once it is our turn to talk to Redis. Whoever changed `channels` last then has
+    /// the last word, however the round trips interleave.
+    async fn sync(&self, id: &str) -> redis::RedisResult<()> {
+        let mut sink = self.sink.lock().await;
+        if let Some(sink) = sink.as_mut() {
+            match self.is_watched(id) {
+                true => sink.subscribe(id).await?,
+                false => sink.unsubscribe(id).await?,
+            }
+        }
+        Ok(())
+    }
+
+    async fn leave(&self, id: &str) {
+        let last = {
+            let mut channels = self.channels.lock().unwrap();
+            let last = match channels.get_mut(id) {
+                Some(channel) => {
+                    channel.watchers -= 1;
+                    channel.watchers == 0
+                },
+                None => false,
+            };
+            if last {
+                channels.remove(id);
+            }
+            last
+        };
+        if last {
+            let _ = self.sync(id).await;
+        }
+    }
+}
+
+impl SubscriptionHub {
+    pub fn new(client: redis::Client) -> Self {
+        Self {
+            client,
+            hub: Arc::new(Hub::default()),
+        }
+    }
+
+    pub async fn subscribe(&self, id: &str) -> StoreResult<BoxStream<'static, Meeting>> {
+        {
+            let mut sink = self.hub.sink.lock().await;
+            if sink.is_none() {
+                let (new_sink, messages) = self.client
+                    .get_async_pubsub()
+                    .await
+                    .map_err(unavailable)?
+                    .split();
+                *sink = Some(new_sink);
+                tokio::spawn(forward(self.client.clone(), Arc::clone(&self.hub), messages));
+            }
+        }
+        let (mut receiver, first) = {
+            let mut channels = self.hub.channels.lock().unwrap();
+            match channels.get_mut(id) {
+                Some(channel) => {
+                    channel.watchers += 1;
+                    (channel.sender.subscribe(), false)
+                },
+                None => {
+                    let (sender, receiver) = broadcast::channel(CHANNEL_CAPACITY);
+                    channels.insert(id.to_string(), Channel { sender, watchers: 1 });
+                    (receiver, true)
+                },
+            }
+        };
+        // Leaves the channel again if subscribing on Redis fails below.
+        let watch = Watch {
+            id: id.to_string(),
+            hub: Arc::clone(&self.hub),
+        };
+        if first {
+            self.hub.sync(id).await.map_err(unavailable)?;
+        }
+        Ok(Box::pin(async_stream::stream! {
+            let _watch = watch;
+            loop {
+                match receiver.recv().await {
+                    Ok(meeting) => yield meeting,
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        }))
+    }
+}
+
+async fn forward(client: redis::Client, hub: Arc<Hub>, mut messages: PubSubStream) {
+    loop {
+        while let Some(msg) = messages.next().await {
+            let meeting = match msg.get_payload::<String>().ok().and_then(|p| parse(&p).ok()) {
+                Some(meeting) => meeting,
+                None => continue,
+            };
+            let channels = hub.channels.lock().unwrap();
+            if let Some(channel) = channels.get(msg.get_channel_name()) {
+                let _ = channel.sender.send(meeting);
+            }
+        }
+        tracing::warn!("lost redis pub/sub connection, reconnecting");
+        messages = loop {
+            tokio::time::sleep(RECONNECT_DELAY).await;
+            match resubscribe(&client, &hub).await {
+                Ok(messages) => break messages,
+                Err(er) => tracing::error!("failed to reconnect redis pub/sub: {}", er),
+            }
+        };
+    }
+}
+
+async fn resubscribe(client: &redis::Client, hub: &Hub) -> redis::RedisResult<PubSubStream> {
+    let mut sink = hub.sink.lock().await;
+    let (mut new_sink, messages) = client.get_async_pubsub().await?.split();
+    let ids: Vec<String> = hub.channels.lock().unwrap().keys().cloned().collect();
+    for id in ids {
+        new_sink.subscribe(&id).await?;
+    }
+    *sink = Some(new_sink);
+    Ok(messages)
+}
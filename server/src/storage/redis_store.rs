@@ -0,0 +1,139 @@
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use deadpool_redis::{Config, CreatePoolError, Pool, PoolError, Runtime};
+use redis::{AsyncCommands, RedisError};
+use std::{fmt, time::Duration};
+use crate::models::meeting::Meeting;
+use super::{redis_hub::SubscriptionHub, MeetingStore, StoreError, StoreResult};
+
+/// Stores every meeting as a JSON string under its id and
+/// uses the same id as the pub/sub channel.
+/// Ids of meetings with a reaction reset schedule are kept in a set besides.
+///
+/// Commands run on pooled async connections, so each WATCH/MULTI
+/// transaction has a connection of its own and meetings never wait
+/// on one another. Watchers share a single pub/sub connection.
+pub struct RedisStore {
+    pool: Pool,
+    hub: SubscriptionHub,
+    retention: Option<Duration>,
+}
+
+#[derive(Debug)]
+pub enum OpenError {
+    Client(RedisError),
+    Pool(CreatePoolError),
+}
+
+impl fmt::Display for OpenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OpenError::Client(er) => write!(f, "invalid redis url: {}", er),
+            OpenError::Pool(er) => write!(f, "failed to create redis pool: {}", er),
+        }
+    }
+}
+
+impl RedisStore {
+    /// With a `retention`, every save (re)sets the key's TTL so Redis
+    /// drops meetings nobody touched for that long.
+    pub fn open(url: &str, retention: Option<Duration>) -> Result<Self, OpenError> {
+        let client = redis::Client::open(url).map_err(OpenError::Client)?;
+        let pool = Config::from_url(url)
+            .create_pool(Some(Runtime::Tokio1))
+            .map_err(OpenError::Pool)?;
+        Ok(Self { pool, hub: SubscriptionHub::new(client), retention })
+    }
+
+    async fn connection(&self) -> StoreResult<deadpool_redis::Connection> {
+        self.pool
+            .get()
+            .await
+            .map_err(|er: PoolError| StoreError::Unavailable(er.to_string()))
+    }
+
+    /// Writes `new` if the watched key still holds `current`. Leaves the key watched
+    /// unless the transaction was executed.
+    async fn write_watched(&self, conn: &mut deadpool_redis::Connection, id: &str, current: Option<&Meeting>, new: &Meeting) -> StoreResult<bool> {
+        let data: Option<String> = conn.get(id).await.map_err(unavailable)?;
+        let stored = data.map(|d| parse(&d)).transpose()?;
+        if stored.as_ref() != current {
+            return Ok(false);
+        }
+        let mut pipe = redis::pipe();
+        pipe.atomic().set(id, to_json(new)?).ignore();
+        if let Some(retention) = self.retention {
+            pipe.expire(id, retention.as_secs() as i64).ignore();
+        }
+        match new.is_scheduled() {
+            true => pipe.sadd(SCHEDULED_KEY, id).ignore(),
+            false => pipe.srem(SCHEDULED_KEY, id).ignore(),
+        };
+        let result: Option<()> = pipe
+            .query_async(conn)
+            .await
+            .map_err(unavailable)?;
+        Ok(result.is_some())
+    }
+}
+
+const SCHEDULED_KEY: &str = "scheduled_ids";
+
+fn unavailable(er: RedisError) -> StoreError {
+    StoreError::Unavailable(er.to_string())
+}
+
+pub(super) fn parse(data: &str) -> StoreResult<Meeting> {
+    serde_json::from_str(data).map_err(|er| StoreError::Broken(er.to_string()))
+}
+
+fn to_json(meeting: &Meeting) -> StoreResult<String> {
+    serde_json::to_string(meeting).map_err(|er| StoreError::Broken(er.to_string()))
+}
+
+#[async_trait]
+impl MeetingStore for RedisStore {
+    async fn get(&self, id: &str) -> StoreResult<Option<Meeting>> {
+        let mut conn = self.connection().await?;
+        let data: Option<String> = conn.get(id).await.map_err(unavailable)?;
+        data.map(|d| parse(&d)).transpose()
+    }
+
+    async fn compare_and_set(&self, id: &str, current: Option<&Meeting>, new: &Meeting) -> StoreResult<bool> {
+        let mut conn = self.connection().await?;
+        redis::cmd("WATCH").arg(id).query_async::<()>(&mut conn).await.map_err(unavailable)?;
+        let written = self.write_watched(&mut conn, id, current, new).await;
+        if !matches!(written, Ok(true)) {
+            // EXEC clears the WATCH, but it may not have run. A connection still watching
+            // would abort the next transaction made on it, so it must not go back to the pool.
+            if redis::cmd("UNWATCH").query_async::<()>(&mut conn).await.is_err() {
+                drop(deadpool_redis::Connection::take(conn));
+            }
+        }
+        written
+    }
+
+    async fn publish(&self, id: &str, meeting: &Meeting) -> StoreResult<()> {
+        let mut conn = self.connection().await?;
+        conn.publish::<&str, String, i32>(id, to_json(meeting)?).await.map_err(unavailable)?;
+        Ok(())
+    }
+
+    async fn subscribe(&self, id: &str) -> StoreResult<BoxStream<'static, Meeting>> {
+        self.hub.subscribe(id).await
+    }
+
+    async fn scheduled_ids(&self) -> StoreResult<Vec<String>> {
+        let mut conn = self.connection().await?;
+        let ids: Vec<String> = conn.smembers(SCHEDULED_KEY).await.map_err(unavailable)?;
+        let mut scheduled = Vec::new();
+        for id in ids {
+            // Meetings expired by their TTL leave their id behind.
+            match conn.exists(&id).await.map_err(unavailable)? {
+                true => scheduled.push(id),
+                false => conn.srem::<&str, &str, ()>(SCHEDULED_KEY, &id).await.map_err(unavailable)?,
+            }
+        }
+        Ok(scheduled)
+    }
+}
@@ -0,0 +1,874 @@
+use async_graphql::ID;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use rusqlite::{params, Connection, OptionalExtension, TransactionBehavior};
+use std::{sync::{Arc, Mutex}, time::{SystemTime, UNIX_EPOCH}};
+use crate::models::{async_standup::{AsyncStandup, AsyncSubmission, AsyncSummary}, history::OrderSnapshot, leader::LeaderTerm, memo::MemoEdit, notes::MemberNotes, parking_lot::ParkingItem, poker::{PokerRound, PokerVote}, reaction::{default_reactions, Reaction}, reaction_reset::ReactionReset, meeting::{Meeting, Member}, timer::SpeakerTimer};
+use serde::{de::DeserializeOwned, Serialize};
+use super::{Channels, MeetingStore, StoreError, StoreResult};
+
+/// Schema changes, applied in order. `PRAGMA user_version` remembers
+/// how many of them a database file has already seen.
+const MIGRATIONS: &[&str] = &[
+    r#"
+    CREATE TABLE meetings (
+        id TEXT PRIMARY KEY,
+        leader_id TEXT,
+        memo TEXT NOT NULL
+    );
+    CREATE TABLE members (
+        meeting_id TEXT NOT NULL REFERENCES meetings(id) ON DELETE CASCADE,
+        id TEXT NOT NULL,
+        position INTEGER NOT NULL,
+        name TEXT NOT NULL,
+        reaction TEXT NOT NULL,
+        PRIMARY KEY (meeting_id, id)
+    );
+    "#,
+    r#"
+    ALTER TABLE meetings ADD COLUMN version INTEGER NOT NULL DEFAULT 0;
+    "#,
+    r#"
+    ALTER TABLE meetings ADD COLUMN saved_at INTEGER NOT NULL DEFAULT 0;
+    UPDATE meetings SET saved_at = strftime('%s', 'now');
+    CREATE INDEX meetings_saved_at ON meetings (saved_at);
+    "#,
+    r#"
+    ALTER TABLE meetings ADD COLUMN timer_duration_secs INTEGER NOT NULL DEFAULT 120;
+    ALTER TABLE meetings ADD COLUMN timer_speaker_id TEXT;
+    ALTER TABLE meetings ADD COLUMN timer_started_at INTEGER;
+    ALTER TABLE meetings ADD COLUMN timer_remaining_ms INTEGER NOT NULL DEFAULT 120000;
+    "#,
+    r#"
+    ALTER TABLE meetings ADD COLUMN current_speaker_id TEXT;
+    UPDATE meetings SET current_speaker_id = timer_speaker_id;
+    ALTER TABLE meetings DROP COLUMN timer_speaker_id;
+    "#,
+    r#"
+    ALTER TABLE members ADD COLUMN attendance TEXT NOT NULL DEFAULT 'PRESENT';
+    "#,
+    r#"
+    ALTER TABLE meetings ADD COLUMN leader_strategy TEXT NOT NULL DEFAULT 'RANDOM';
+    CREATE TABLE leader_terms (
+        meeting_id TEXT NOT NULL REFERENCES meetings(id) ON DELETE CASCADE,
+        position INTEGER NOT NULL,
+        member_id TEXT NOT NULL,
+        chosen_at INTEGER NOT NULL,
+        PRIMARY KEY (meeting_id, position)
+    );
+    "#,
+    r#"
+    CREATE TABLE order_snapshots (
+        meeting_id TEXT NOT NULL REFERENCES meetings(id) ON DELETE CASCADE,
+        position INTEGER NOT NULL,
+        member_ids TEXT NOT NULL,
+        recorded_at INTEGER NOT NULL,
+        PRIMARY KEY (meeting_id, position)
+    );
+    "#,
+    r#"
+    ALTER TABLE meetings ADD COLUMN order_strategy TEXT NOT NULL DEFAULT 'SHUFFLE';
+    ALTER TABLE meetings ADD COLUMN order_seed INTEGER;
+    ALTER TABLE members ADD COLUMN pin TEXT NOT NULL DEFAULT 'NONE';
+    "#,
+    r#"
+    CREATE TABLE parking_items (
+        meeting_id TEXT NOT NULL REFERENCES meetings(id) ON DELETE CASCADE,
+        id TEXT NOT NULL,
+        position INTEGER NOT NULL,
+        text TEXT NOT NULL,
+        author_id TEXT,
+        owner_id TEXT,
+        created_at INTEGER NOT NULL,
+        resolved INTEGER NOT NULL,
+        PRIMARY KEY (meeting_id, id)
+    );
+    "#,
+    r#"
+    ALTER TABLE meetings ADD COLUMN memo_revision INTEGER NOT NULL DEFAULT 0;
+    CREATE TABLE memo_edits (
+        meeting_id TEXT NOT NULL REFERENCES meetings(id) ON DELETE CASCADE,
+        revision INTEGER NOT NULL,
+        client_id TEXT NOT NULL,
+        ops TEXT NOT NULL,
+        PRIMARY KEY (meeting_id, revision)
+    );
+    "#,
+    r#"
+    ALTER TABLE meetings ADD COLUMN poker_id TEXT;
+    ALTER TABLE meetings ADD COLUMN poker_topic TEXT;
+    ALTER TABLE meetings ADD COLUMN poker_started_at INTEGER;
+    ALTER TABLE meetings ADD COLUMN poker_revealed INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE meetings ADD COLUMN poker_facilitator_token TEXT;
+    CREATE TABLE poker_votes (
+        meeting_id TEXT NOT NULL REFERENCES meetings(id) ON DELETE CASCADE,
+        position INTEGER NOT NULL,
+        member_id TEXT NOT NULL,
+        reaction TEXT NOT NULL,
+        PRIMARY KEY (meeting_id, position)
+    );
+    "#,
+    r#"
+    CREATE TABLE reactions (
+        meeting_id TEXT NOT NULL REFERENCES meetings(id) ON DELETE CASCADE,
+        position INTEGER NOT NULL,
+        key TEXT NOT NULL,
+        symbol TEXT NOT NULL,
+        label TEXT NOT NULL,
+        points INTEGER,
+        PRIMARY KEY (meeting_id, position)
+    );
+    ALTER TABLE poker_votes ADD COLUMN points INTEGER NOT NULL DEFAULT 0;
+    UPDATE poker_votes SET points = CASE reaction
+        WHEN 'I' THEN 1 WHEN 'II' THEN 2 WHEN 'III' THEN 3 WHEN 'IV' THEN 4 WHEN 'V' THEN 5
+        WHEN 'VI' THEN 6 WHEN 'VII' THEN 7 WHEN 'VIII' THEN 8 WHEN 'IX' THEN 9 WHEN 'X' THEN 10
+        ELSE 0 END;
+    "#,
+    r#"
+    ALTER TABLE members ADD COLUMN reaction_at INTEGER;
+    ALTER TABLE meetings ADD COLUMN reset_time_zone TEXT;
+    ALTER TABLE meetings ADD COLUMN reset_hour INTEGER;
+    ALTER TABLE meetings ADD COLUMN reset_minute INTEGER;
+    "#,
+    r#"
+    ALTER TABLE members ADD COLUMN notes_yesterday TEXT NOT NULL DEFAULT '';
+    ALTER TABLE members ADD COLUMN notes_today TEXT NOT NULL DEFAULT '';
+    ALTER TABLE members ADD COLUMN notes_blockers TEXT NOT NULL DEFAULT '';
+    "#,
+    r#"
+    ALTER TABLE meetings ADD COLUMN async_opened_at INTEGER;
+    ALTER TABLE meetings ADD COLUMN async_deadline INTEGER;
+    CREATE TABLE async_submissions (
+        meeting_id TEXT NOT NULL REFERENCES meetings(id) ON DELETE CASCADE,
+        position INTEGER NOT NULL,
+        member_id TEXT NOT NULL,
+        submitted_at INTEGER NOT NULL,
+        PRIMARY KEY (meeting_id, position)
+    );
+    CREATE TABLE async_summaries (
+        meeting_id TEXT NOT NULL REFERENCES meetings(id) ON DELETE CASCADE,
+        position INTEGER NOT NULL,
+        date TEXT NOT NULL,
+        opened_at INTEGER NOT NULL,
+        closed_at INTEGER NOT NULL,
+        updates TEXT NOT NULL,
+        PRIMARY KEY (meeting_id, position)
+    );
+    "#,
+    r#"
+    ALTER TABLE async_submissions ADD COLUMN notes_yesterday TEXT NOT NULL DEFAULT '';
+    ALTER TABLE async_submissions ADD COLUMN notes_today TEXT NOT NULL DEFAULT '';
+    ALTER TABLE async_submissions ADD COLUMN notes_blockers TEXT NOT NULL DEFAULT '';
+    ALTER TABLE async_submissions ADD COLUMN reaction TEXT;
+    "#,
+];
+
+/// Keeps meetings in a SQLite file so boards survive restarts.
+/// Watchers are notified in process, so run a single server per file.
+pub struct SqliteStore {
+    conn: Arc<Mutex<Connection>>,
+    channels: Channels,
+}
+
+impl SqliteStore {
+    pub fn open(path: &str) -> rusqlite::Result<Self> {
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    fn with_connection(mut conn: Connection) -> rusqlite::Result<Self> {
+        conn.pragma_update(None, "foreign_keys", true)?;
+        migrate(&mut conn)?;
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+            channels: Channels::default(),
+        })
+    }
+
+    async fn run<T, F>(&self, f: F) -> StoreResult<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Connection) -> StoreResult<T> + Send + 'static,
+    {
+        let conn = Arc::clone(&self.conn);
+        tokio::task::spawn_blocking(move || f(&mut conn.lock().unwrap()))
+            .await
+            .map_err(|er| StoreError::Unavailable(er.to_string()))?
+    }
+}
+
+fn migrate(conn: &mut Connection) -> rusqlite::Result<()> {
+    let version: usize = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    for (i, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(migration)?;
+        tx.pragma_update(None, "user_version", i + 1)?;
+        tx.commit()?;
+    }
+    Ok(())
+}
+
+fn unix_seconds(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH).map(|d| d.as_secs() as i64).unwrap_or(0)
+}
+
+fn unavailable(er: rusqlite::Error) -> StoreError {
+    StoreError::Unavailable(er.to_string())
+}
+
+/// Stores a unit enum, such as an attendance, by its serialized name.
+fn enum_to_sql<T: Serialize>(value: T) -> StoreResult<String> {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(name)) => Ok(name),
+        _ => Err(StoreError::Broken(String::from("unknown enum value"))),
+    }
+}
+
+fn enum_from_sql<T: DeserializeOwned>(name: String) -> StoreResult<T> {
+    serde_json::from_value(serde_json::Value::String(name))
+        .map_err(|er| StoreError::Broken(er.to_string()))
+}
+
+fn load(conn: &Connection, id: &str) -> StoreResult<Option<Meeting>> {
+    let row = conn
+        .query_row(
+            "SELECT version, leader_id, leader_strategy, order_strategy, order_seed, memo, memo_revision, current_speaker_id,
+                timer_duration_secs, timer_started_at, timer_remaining_ms,
+                poker_id, poker_topic, poker_started_at, poker_revealed, poker_facilitator_token,
+                reset_time_zone, reset_hour, reset_minute, async_opened_at, async_deadline
+             FROM meetings WHERE id = ?1",
+            params![id],
+            |row| {
+                let timer = SpeakerTimer {
+                    duration_secs: row.get("timer_duration_secs")?,
+                    started_at: row.get("timer_started_at")?,
+                    remaining_ms: row.get("timer_remaining_ms")?,
+                };
+                let poker = match row.get::<_, Option<String>>("poker_id")? {
+                    Some(poker_id) => Some(PokerRound {
+                        id: ID(poker_id),
+                        topic: row.get("poker_topic")?,
+                        started_at: row.get("poker_started_at")?,
+                        revealed: row.get("poker_revealed")?,
+                        facilitator_token: row.get("poker_facilitator_token")?,
+                        votes: Vec::new(),
+                    }),
+                    None => None,
+                };
+                let reaction_reset = match row.get::<_, Option<String>>("reset_time_zone")? {
+                    Some(time_zone) => Some(ReactionReset {
+                        time_zone,
+                        hour: row.get("reset_hour")?,
+                        minute: row.get("reset_minute")?,
+                    }),
+                    None => None,
+                };
+                let async_standup = match row.get::<_, Option<u64>>("async_deadline")? {
+                    Some(deadline) => Some(AsyncStandup {
+                        opened_at: row.get("async_opened_at")?,
+                        deadline,
+                        submissions: Vec::new(),
+                    }),
+                    None => None,
+                };
+                Ok((
+                    row.get::<_, u64>("version")?,
+                    row.get("leader_id")?,
+                    row.get::<_, String>("leader_strategy")?,
+                    row.get::<_, String>("order_strategy")?,
+                    // Stored as the bits of an i64, SQLite has no unsigned integers.
+                    row.get::<_, Option<i64>>("order_seed")?.map(|seed| seed as u64),
+                    row.get("memo")?,
+                    row.get::<_, u64>("memo_revision")?,
+                    row.get("current_speaker_id")?,
+                    timer,
+                    poker,
+                    reaction_reset,
+                    async_standup,
+                ))
+            },
+        )
+        .optional()
+        .map_err(unavailable)?;
+    let (version, leader_id, leader_strategy, order_strategy, order_seed, memo, memo_revision, current_speaker_id, timer, mut poker, reaction_reset, mut async_standup) = match row {
+        Some(row) => row,
+        None => return Ok(None),
+    };
+    let mut stmt = conn
+        .prepare("SELECT id, name, reaction, reaction_at, attendance, pin, notes_yesterday, notes_today, notes_blockers FROM members WHERE meeting_id = ?1 ORDER BY position")
+        .map_err(unavailable)?;
+    let rows = stmt
+        .query_map(params![id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<u64>>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, String>(5)?,
+                MemberNotes {
+                    yesterday: row.get(6)?,
+                    today: row.get(7)?,
+                    blockers: row.get(8)?,
+                },
+            ))
+        })
+        .map_err(unavailable)?;
+    let mut members = Vec::new();
+    for row in rows {
+        let (member_id, name, reaction, reaction_at, attendance, pin, notes) = row.map_err(unavailable)?;
+        members.push(Member {
+            id: ID(member_id),
+            name,
+            reaction,
+            reaction_at,
+            attendance: enum_from_sql(attendance)?,
+            pin: enum_from_sql(pin)?,
+            notes,
+        });
+    }
+    let mut stmt = conn
+        .prepare("SELECT member_id, chosen_at FROM leader_terms WHERE meeting_id = ?1 ORDER BY position")
+        .map_err(unavailable)?;
+    let leader_history = stmt
+        .query_map(params![id], |row| {
+            Ok(LeaderTerm {
+                member_id: row.get(0)?,
+                chosen_at: row.get(1)?,
+            })
+        })
+        .map_err(unavailable)?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(unavailable)?;
+    let mut stmt = conn
+        .prepare("SELECT member_ids, recorded_at FROM order_snapshots WHERE meeting_id = ?1 ORDER BY position")
+        .map_err(unavailable)?;
+    let rows = stmt
+        .query_map(params![id], |row| Ok((row.get::<_, String>(0)?, row.get::<_, u64>(1)?)))
+        .map_err(unavailable)?;
+    let mut order_history = Vec::new();
+    for row in rows {
+        let (member_ids, recorded_at) = row.map_err(unavailable)?;
+        order_history.push(OrderSnapshot {
+            member_ids: serde_json::from_str(&member_ids).map_err(|er| StoreError::Broken(er.to_string()))?,
+            recorded_at,
+        });
+    }
+    let mut stmt = conn
+        .prepare("SELECT key, symbol, label, points FROM reactions WHERE meeting_id = ?1 ORDER BY position")
+        .map_err(unavailable)?;
+    let mut reactions = stmt
+        .query_map(params![id], |row| {
+            Ok(Reaction {
+                key: row.get(0)?,
+                symbol: row.get(1)?,
+                label: row.get(2)?,
+                points: row.get(3)?,
+            })
+        })
+        .map_err(unavailable)?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(unavailable)?;
+    if reactions.is_empty() {
+        // Stored before meetings had their own reactions, a meeting always has at least one.
+        reactions = default_reactions();
+    }
+    if let Some(poker) = poker.as_mut() {
+        let mut stmt = conn
+            .prepare("SELECT member_id, reaction, points FROM poker_votes WHERE meeting_id = ?1 ORDER BY position")
+            .map_err(unavailable)?;
+        poker.votes = stmt
+            .query_map(params![id], |row| {
+                Ok(PokerVote {
+                    member_id: row.get(0)?,
+                    reaction: row.get(1)?,
+                    points: row.get(2)?,
+                })
+            })
+            .map_err(unavailable)?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(unavailable)?;
+    }
+    if let Some(standup) = async_standup.as_mut() {
+        let mut stmt = conn
+            .prepare(
+                "SELECT member_id, submitted_at, notes_yesterday, notes_today, notes_blockers, reaction
+                 FROM async_submissions WHERE meeting_id = ?1 ORDER BY position"
+            )
+            .map_err(unavailable)?;
+        standup.submissions = stmt
+            .query_map(params![id], |row| {
+                Ok(AsyncSubmission {
+                    member_id: row.get(0)?,
+                    submitted_at: row.get(1)?,
+                    notes: MemberNotes {
+                        yesterday: row.get(2)?,
+                        today: row.get(3)?,
+                        blockers: row.get(4)?,
+                    },
+                    reaction: row.get(5)?,
+                })
+            })
+            .map_err(unavailable)?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(unavailable)?;
+    }
+    let mut stmt = conn
+        .prepare("SELECT date, opened_at, closed_at, updates FROM async_summaries WHERE meeting_id = ?1 ORDER BY position")
+        .map_err(unavailable)?;
+    let rows = stmt
+        .query_map(params![id], |row| Ok((row.get::<_, String>(0)?, row.get::<_, u64>(1)?, row.get::<_, u64>(2)?, row.get::<_, String>(3)?)))
+        .map_err(unavailable)?;
+    let mut async_summaries = Vec::new();
+    for row in rows {
+        let (date, opened_at, closed_at, updates) = row.map_err(unavailable)?;
+        async_summaries.push(AsyncSummary {
+            date,
+            opened_at,
+            closed_at,
+            updates: serde_json::from_str(&updates).map_err(|er| StoreError::Broken(er.to_string()))?,
+        });
+    }
+    let mut stmt = conn
+        .prepare("SELECT revision, client_id, ops FROM memo_edits WHERE meeting_id = ?1 ORDER BY revision")
+        .map_err(unavailable)?;
+    let rows = stmt
+        .query_map(params![id], |row| Ok((row.get::<_, u64>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?)))
+        .map_err(unavailable)?;
+    let mut memo_edits = Vec::new();
+    for row in rows {
+        let (revision, client_id, ops) = row.map_err(unavailable)?;
+        memo_edits.push(MemoEdit {
+            revision,
+            client_id,
+            ops: serde_json::from_str(&ops).map_err(|er| StoreError::Broken(er.to_string()))?,
+        });
+    }
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, text, author_id, owner_id, created_at, resolved
+             FROM parking_items WHERE meeting_id = ?1 ORDER BY position"
+        )
+        .map_err(unavailable)?;
+    let parking_lot = stmt
+        .query_map(params![id], |row| {
+            Ok(ParkingItem {
+                id: ID(row.get("id")?),
+                text: row.get("text")?,
+                author_id: row.get("author_id")?,
+                owner_id: row.get("owner_id")?,
+                created_at: row.get("created_at")?,
+                resolved: row.get("resolved")?,
+            })
+        })
+        .map_err(unavailable)?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(unavailable)?;
+    Ok(Some(Meeting {
+        id: ID(id.to_string()),
+        version,
+        leader_id,
+        leader_strategy: enum_from_sql(leader_strategy)?,
+        leader_history,
+        order_history,
+        order_strategy: enum_from_sql(order_strategy)?,
+        order_seed,
+        members,
+        reactions,
+        reaction_reset,
+        memo,
+        memo_revision,
+        memo_edits,
+        parking_lot,
+        current_speaker_id,
+        timer,
+        poker,
+        async_standup,
+        async_summaries,
+    }))
+}
+
+fn store(conn: &Connection, meeting: &Meeting) -> StoreResult<()> {
+    let id = meeting.id.to_string();
+    let poker = meeting.poker.as_ref();
+    let reaction_reset = meeting.reaction_reset.as_ref();
+    let async_standup = meeting.async_standup.as_ref();
+    conn.execute(
+        "INSERT INTO meetings (
+            id, version, leader_id, leader_strategy, order_strategy, order_seed, memo, memo_revision, saved_at,
+            current_speaker_id, timer_duration_secs, timer_started_at, timer_remaining_ms,
+            poker_id, poker_topic, poker_started_at, poker_revealed, poker_facilitator_token,
+            reset_time_zone, reset_hour, reset_minute, async_opened_at, async_deadline
+         ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23)
+         ON CONFLICT(id) DO UPDATE SET
+            version = excluded.version,
+            leader_id = excluded.leader_id,
+            leader_strategy = excluded.leader_strategy,
+            order_strategy = excluded.order_strategy,
+            order_seed = excluded.order_seed,
+            memo = excluded.memo,
+            memo_revision = excluded.memo_revision,
+            saved_at = excluded.saved_at,
+            current_speaker_id = excluded.current_speaker_id,
+            timer_duration_secs = excluded.timer_duration_secs,
+            timer_started_at = excluded.timer_started_at,
+            timer_remaining_ms = excluded.timer_remaining_ms,
+            poker_id = excluded.poker_id,
+            poker_topic = excluded.poker_topic,
+            poker_started_at = excluded.poker_started_at,
+            poker_revealed = excluded.poker_revealed,
+            poker_facilitator_token = excluded.poker_facilitator_token,
+            reset_time_zone = excluded.reset_time_zone,
+            reset_hour = excluded.reset_hour,
+            reset_minute = excluded.reset_minute,
+            async_opened_at = excluded.async_opened_at,
+            async_deadline = excluded.async_deadline",
+        params![
+            id,
+            meeting.version,
+            meeting.leader_id,
+            enum_to_sql(meeting.leader_strategy)?,
+            enum_to_sql(meeting.order_strategy)?,
+            meeting.order_seed.map(|seed| seed as i64),
+            meeting.memo,
+            meeting.memo_revision,
+            unix_seconds(SystemTime::now()),
+            meeting.current_speaker_id,
+            meeting.timer.duration_secs,
+            meeting.timer.started_at,
+            meeting.timer.remaining_ms,
+            poker.map(|p| p.id.to_string()),
+            poker.map(|p| &p.topic),
+            poker.map(|p| p.started_at),
+            poker.is_some_and(|p| p.revealed),
+            poker.map(|p| &p.facilitator_token),
+            reaction_reset.map(|r| &r.time_zone),
+            reaction_reset.map(|r| r.hour),
+            reaction_reset.map(|r| r.minute),
+            async_standup.map(|s| s.opened_at),
+            async_standup.map(|s| s.deadline),
+        ],
+    ).map_err(unavailable)?;
+    conn.execute("DELETE FROM members WHERE meeting_id = ?1", params![id])
+        .map_err(unavailable)?;
+    let mut stmt = conn
+        .prepare("INSERT INTO members (meeting_id, id, position, name, reaction, reaction_at, attendance, pin, notes_yesterday, notes_today, notes_blockers) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)")
+        .map_err(unavailable)?;
+    for (position, member) in meeting.members.iter().enumerate() {
+        stmt.execute(params![
+            id,
+            member.id.to_string(),
+            position,
+            member.name,
+            member.reaction,
+            member.reaction_at,
+            enum_to_sql(member.attendance)?,
+            enum_to_sql(member.pin)?,
+            member.notes.yesterday,
+            member.notes.today,
+            member.notes.blockers,
+        ]).map_err(unavailable)?;
+    }
+    conn.execute("DELETE FROM leader_terms WHERE meeting_id = ?1", params![id])
+        .map_err(unavailable)?;
+    let mut stmt = conn
+        .prepare("INSERT INTO leader_terms (meeting_id, position, member_id, chosen_at) VALUES (?1, ?2, ?3, ?4)")
+        .map_err(unavailable)?;
+    for (position, term) in meeting.leader_history.iter().enumerate() {
+        stmt.execute(params![id, position, term.member_id, term.chosen_at])
+            .map_err(unavailable)?;
+    }
+    conn.execute("DELETE FROM order_snapshots WHERE meeting_id = ?1", params![id])
+        .map_err(unavailable)?;
+    let mut stmt = conn
+        .prepare("INSERT INTO order_snapshots (meeting_id, position, member_ids, recorded_at) VALUES (?1, ?2, ?3, ?4)")
+        .map_err(unavailable)?;
+    for (position, order) in meeting.order_history.iter().enumerate() {
+        let member_ids = serde_json::to_string(&order.member_ids)
+            .map_err(|er| StoreError::Broken(er.to_string()))?;
+        stmt.execute(params![id, position, member_ids, order.recorded_at])
+            .map_err(unavailable)?;
+    }
+    conn.execute("DELETE FROM poker_votes WHERE meeting_id = ?1", params![id])
+        .map_err(unavailable)?;
+    let mut stmt = conn
+        .prepare("INSERT INTO poker_votes (meeting_id, position, member_id, reaction, points) VALUES (?1, ?2, ?3, ?4, ?5)")
+        .map_err(unavailable)?;
+    for (position, vote) in poker.iter().flat_map(|p| p.votes.iter()).enumerate() {
+        stmt.execute(params![id, position, vote.member_id, vote.reaction, vote.points])
+            .map_err(unavailable)?;
+    }
+    conn.execute("DELETE FROM async_submissions WHERE meeting_id = ?1", params![id])
+        .map_err(unavailable)?;
+    let mut stmt = conn
+        .prepare(
+            "INSERT INTO async_submissions
+             (meeting_id, position, member_id, submitted_at, notes_yesterday, notes_today, notes_blockers, reaction)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)"
+        )
+        .map_err(unavailable)?;
+    for (position, submission) in async_standup.iter().flat_map(|s| s.submissions.iter()).enumerate() {
+        let notes = &submission.notes;
+        stmt.execute(params![
+            id,
+            position,
+            submission.member_id,
+            submission.submitted_at,
+            notes.yesterday,
+            notes.today,
+            notes.blockers,
+            submission.reaction,
+        ])
+            .map_err(unavailable)?;
+    }
+    conn.execute("DELETE FROM async_summaries WHERE meeting_id = ?1", params![id])
+        .map_err(unavailable)?;
+    let mut stmt = conn
+        .prepare("INSERT INTO async_summaries (meeting_id, position, date, opened_at, closed_at, updates) VALUES (?1, ?2, ?3, ?4, ?5, ?6)")
+        .map_err(unavailable)?;
+    for (position, summary) in meeting.async_summaries.iter().enumerate() {
+        let updates = serde_json::to_string(&summary.updates)
+            .map_err(|er| StoreError::Broken(er.to_string()))?;
+        stmt.execute(params![id, position, summary.date, summary.opened_at, summary.closed_at, updates])
+            .map_err(unavailable)?;
+    }
+    conn.execute("DELETE FROM reactions WHERE meeting_id = ?1", params![id])
+        .map_err(unavailable)?;
+    let mut stmt = conn
+        .prepare("INSERT INTO reactions (meeting_id, position, key, symbol, label, points) VALUES (?1, ?2, ?3, ?4, ?5, ?6)")
+        .map_err(unavailable)?;
+    for (position, reaction) in meeting.reactions.iter().enumerate() {
+        stmt.execute(params![id, position, reaction.key, reaction.symbol, reaction.label, reaction.points])
+            .map_err(unavailable)?;
+    }
+    conn.execute("DELETE FROM memo_edits WHERE meeting_id = ?1", params![id])
+        .map_err(unavailable)?;
+    let mut stmt = conn
+        .prepare("INSERT INTO memo_edits (meeting_id, revision, client_id, ops) VALUES (?1, ?2, ?3, ?4)")
+        .map_err(unavailable)?;
+    for edit in &meeting.memo_edits {
+        let ops = serde_json::to_string(&edit.ops)
+            .map_err(|er| StoreError::Broken(er.to_string()))?;
+        stmt.execute(params![id, edit.revision, edit.client_id, ops])
+            .map_err(unavailable)?;
+    }
+    conn.execute("DELETE FROM parking_items WHERE meeting_id = ?1", params![id])
+        .map_err(unavailable)?;
+    let mut stmt = conn
+        .prepare(
+            "INSERT INTO parking_items (meeting_id, id, position, text, author_id, owner_id, created_at, resolved)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)"
+        )
+        .map_err(unavailable)?;
+    for (position, item) in meeting.parking_lot.iter().enumerate() {
+        stmt.execute(params![
+            id,
+            item.id.to_string(),
+            position,
+            item.text,
+            item.author_id,
+            item.owner_id,
+            item.created_at,
+            item.resolved,
+        ]).map_err(unavailable)?;
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl MeetingStore for SqliteStore {
+    async fn get(&self, id: &str) -> StoreResult<Option<Meeting>> {
+        let id = id.to_string();
+        self.run(move |conn| load(conn, &id)).await
+    }
+
+    async fn compare_and_set(&self, id: &str, current: Option<&Meeting>, new: &Meeting) -> StoreResult<bool> {
+        let id = id.to_string();
+        let current = current.cloned();
+        let new = new.clone();
+        self.run(move |conn| {
+            let tx = conn
+                .transaction_with_behavior(TransactionBehavior::Immediate)
+                .map_err(unavailable)?;
+            if load(&tx, &id)? != current {
+                return Ok(false);
+            }
+            store(&tx, &new)?;
+            tx.commit().map_err(unavailable)?;
+            Ok(true)
+        }).await
+    }
+
+    async fn publish(&self, id: &str, meeting: &Meeting) -> StoreResult<()> {
+        self.channels.publish(id, meeting);
+        Ok(())
+    }
+
+    async fn subscribe(&self, id: &str) -> StoreResult<BoxStream<'static, Meeting>> {
+        Ok(self.channels.subscribe(id))
+    }
+
+    async fn scheduled_ids(&self) -> StoreResult<Vec<String>> {
+        self.run(|conn| {
+            let mut stmt = conn
+                .prepare("SELECT id FROM meetings WHERE reset_time_zone IS NOT NULL OR async_deadline IS NOT NULL")
+                .map_err(unavailable)?;
+            let ids = stmt
+                .query_map([], |row| row.get(0))
+                .map_err(unavailable)?
+                .collect::<rusqlite::Result<Vec<String>>>()
+                .map_err(unavailable)?;
+            Ok(ids)
+        }).await
+    }
+
+    async fn remove_untouched(&self, cutoff: SystemTime) -> StoreResult<usize> {
+        self.run(move |conn| {
+            conn.execute("DELETE FROM meetings WHERE saved_at < ?1", params![unix_seconds(cutoff)])
+                .map_err(unavailable)
+        }).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{async_standup::AsyncUpdate, leader::LeaderStrategy, meeting::Attendance, memo::MemoOp, order::{MemberPin, OrderStrategy}};
+
+    fn store() -> SqliteStore {
+        SqliteStore::with_connection(Connection::open_in_memory().unwrap()).unwrap()
+    }
+
+    fn member(id: &str, name: &str) -> Member {
+        Member {
+            id: ID(id.to_string()),
+            name: name.to_string(),
+            reaction: String::from("happy"),
+            reaction_at: Some(1_000),
+            attendance: Attendance::OutOfOffice,
+            pin: MemberPin::Last,
+            notes: MemberNotes {
+                yesterday: String::from("Reviewed"),
+                today: String::from("Ship it"),
+                blockers: String::from("None"),
+            },
+        }
+    }
+
+    /// Meeting with every field set to something other than its default.
+    fn meeting(id: &str) -> Meeting {
+        Meeting {
+            id: ID(id.to_string()),
+            version: 7,
+            leader_id: Some(String::from("ada")),
+            leader_strategy: LeaderStrategy::RoundRobin,
+            leader_history: vec![LeaderTerm { member_id: String::from("ada"), chosen_at: 2_000 }],
+            order_history: vec![OrderSnapshot { member_ids: vec![String::from("bob"), String::from("ada")], recorded_at: 3_000 }],
+            order_strategy: OrderStrategy::SeededShuffle,
+            order_seed: Some(42),
+            members: vec![member("ada", "Ada"), member("bob", "Bob")],
+            reactions: vec![
+                Reaction { key: String::from("happy"), symbol: String::from("🙂"), label: String::from("happy"), points: None },
+                Reaction { key: String::from("xl"), symbol: String::from("XL"), label: String::new(), points: Some(8) },
+            ],
+            reaction_reset: Some(ReactionReset { time_zone: String::from("Europe/Berlin"), hour: 6, minute: 30 }),
+            memo: String::from("- [x] done"),
+            memo_revision: 1,
+            memo_edits: vec![MemoEdit {
+                revision: 1,
+                client_id: String::from("client"),
+                ops: vec![MemoOp { retain: None, insert: Some(String::from("- [x] done")), delete: None }],
+            }],
+            parking_lot: vec![ParkingItem {
+                id: ID(String::from("item")),
+                text: String::from("Release date"),
+                author_id: Some(String::from("ada")),
+                owner_id: Some(String::from("bob")),
+                created_at: 4_000,
+                resolved: true,
+            }],
+            current_speaker_id: Some(String::from("bob")),
+            timer: SpeakerTimer { duration_secs: 90, started_at: Some(5_000), remaining_ms: -1_500 },
+            poker: Some(PokerRound {
+                id: ID(String::from("round")),
+                topic: String::from("Login page"),
+                started_at: 6_000,
+                revealed: true,
+                facilitator_token: String::from("token"),
+                votes: vec![PokerVote { member_id: String::from("ada"), reaction: String::from("xl"), points: 8 }],
+            }),
+            async_standup: Some(AsyncStandup {
+                opened_at: 7_000,
+                deadline: 8_000,
+                submissions: vec![AsyncSubmission {
+                    member_id: String::from("bob"),
+                    submitted_at: 7_500,
+                    notes: member("bob", "Bob").notes,
+                    reaction: Some(String::from("XL")),
+                }],
+            }),
+            async_summaries: vec![AsyncSummary {
+                date: String::from("2024-01-02"),
+                opened_at: 1_000,
+                closed_at: 2_000,
+                updates: vec![AsyncUpdate {
+                    member_id: String::from("ada"),
+                    name: String::from("Ada"),
+                    reaction: Some(String::from("🙂")),
+                    notes: member("ada", "Ada").notes,
+                    submitted_at: Some(1_500),
+                }],
+            }],
+        }
+    }
+
+    #[test]
+    fn migrates_an_empty_database_to_the_latest_version() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        let version = |conn: &Connection| -> usize { conn.query_row("PRAGMA user_version", [], |row| row.get(0)).unwrap() };
+        assert_eq!(version(&conn), 0);
+        migrate(&mut conn).unwrap();
+        assert_eq!(version(&conn), MIGRATIONS.len());
+        // Migrating again finds nothing left to do.
+        migrate(&mut conn).unwrap();
+        assert_eq!(version(&conn), MIGRATIONS.len());
+    }
+
+    #[tokio::test]
+    async fn saved_meeting_loads_unchanged() {
+        let store = store();
+        let meeting = meeting("meeting");
+        assert!(store.compare_and_set("meeting", None, &meeting).await.unwrap());
+        assert!(store.get("meeting").await.unwrap() == Some(meeting));
+        assert!(store.get("other").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn seeds_beyond_i64_load_unchanged() {
+        let store = store();
+        for seed in [i64::MAX as u64 + 1, u64::MAX] {
+            let mut meeting = meeting("meeting");
+            meeting.order_seed = Some(seed);
+            let current = store.get("meeting").await.unwrap();
+            assert!(store.compare_and_set("meeting", current.as_ref(), &meeting).await.unwrap());
+            assert_eq!(store.get("meeting").await.unwrap().unwrap().order_seed, Some(seed));
+        }
+    }
+
+    #[tokio::test]
+    async fn compare_and_set_rejects_a_stale_meeting() {
+        let store = store();
+        let first = meeting("meeting");
+        assert!(store.compare_and_set("meeting", None, &first).await.unwrap());
+        let mut second = first.clone();
+        second.version += 1;
+        second.memo = String::from("second");
+        assert!(store.compare_and_set("meeting", Some(&first), &second).await.unwrap());
+
+        let mut stale = first.clone();
+        stale.version += 1;
+        stale.memo = String::from("stale");
+        assert!(!store.compare_and_set("meeting", Some(&first), &stale).await.unwrap());
+        assert!(!store.compare_and_set("meeting", None, &stale).await.unwrap());
+        assert!(store.get("meeting").await.unwrap() == Some(second));
+    }
+}